@@ -14,102 +14,200 @@ use std::path::Path;
 const ALT_FLAG: u8 = 1 << 5;
 const ERR_FLAG: u8 = 1 << 6;
 
+#[rustfmt::skip]
 static CODE: [&[u8]; 32] = [
-    &[b'0', b'o', b'O'],
-    &[b'1', b'i', b'I', b'l', b'L'],
-    &[b'2'],
-    &[b'3'],
-    &[b'4'],
-    &[b'5'],
-    &[b'6'],
-    &[b'7'],
-    &[b'8'],
-    &[b'9'],
-    &[b'a', b'A'],
-    &[b'b', b'B'],
-    &[b'c', b'C'],
-    &[b'd', b'D'],
-    &[b'e', b'E'],
-    &[b'f', b'F', b's', b'S'],
-    &[b'g', b'G'],
-    &[b'h', b'H'],
-    &[b'j', b'J'],
-    &[b'k', b'K'],
-    &[b'm', b'M'],
-    &[b'n', b'N'],
-    &[b'p', b'P'],
-    &[b'q', b'Q'],
-    &[b'r', b'R'],
-    &[b't', b'T'],
-    &[b'u', b'U'],
-    &[b'v', b'V'],
-    &[b'w', b'W'],
-    &[b'x', b'X'],
-    &[b'y', b'Y'],
-    &[b'z', b'Z'],
+    b"0oO",
+    b"1iIlL",
+    b"2",
+    b"3",
+    b"4",
+    b"5",
+    b"6",
+    b"7",
+    b"8",
+    b"9",
+    b"aA",
+    b"bB",
+    b"cC",
+    b"dD",
+    b"eE",
+    b"fFsS",
+    b"gG",
+    b"hH",
+    b"jJ",
+    b"kK",
+    b"mM",
+    b"nN",
+    b"pP",
+    b"qQ",
+    b"rR",
+    b"tT",
+    b"uU",
+    b"vV",
+    b"wW",
+    b"xX",
+    b"yY",
+    b"zZ",
 ];
 
-fn main() {
-    let out_dir = env::var_os("OUT_DIR").unwrap();
-    let dest_path = Path::new(&out_dir).join("codec_tables.rs");
+/// QWERTY key rows, each offset by a fraction of a key to the right of the
+/// row above it
+static KEYBOARD: [&[u8]; 4] = [b"1234567890", b"qwertyuiop", b"asdfghjkl", b"zxcvbnm"];
 
-    let encode_table: Vec<_> = CODE.iter().map(|encodings| encodings[0]).collect();
+/// Visually similar characters that are not handled as confusables by the
+/// encoding itself
+static LOOKALIKES: [(u8, u8); 12] = [
+    (b'u', b'v'),
+    (b'v', b'y'),
+    (b'2', b'z'),
+    (b'5', b's'),
+    (b'6', b'b'),
+    (b'8', b'b'),
+    (b'9', b'g'),
+    (b'9', b'q'),
+    (b'g', b'q'),
+    (b'm', b'n'),
+    (b'c', b'e'),
+    (b'1', b'7'),
+];
+
+/// Substitution costs for typo-tolerant lookup
+const NEAR_SUBSTITUTION_COST: u8 = 1;
+const FAR_SUBSTITUTION_COST: u8 = 3;
+
+fn key_position(c: u8) -> Option<(i32, i32)> {
+    KEYBOARD.iter().enumerate().find_map(|(row, keys)| {
+        keys.iter()
+            .position(|&k| k == c.to_ascii_lowercase())
+            .map(|col| (row as i32, col as i32))
+    })
+}
+
+fn are_adjacent_keys(a: u8, b: u8) -> bool {
+    let (Some((ra, ca)), Some((rb, cb))) = (key_position(a), key_position(b)) else {
+        return false;
+    };
+    match rb - ra {
+        0 => (ca - cb).abs() == 1,
+        1 => cb == ca || cb == ca - 1,
+        -1 => cb == ca || cb == ca + 1,
+        _ => false,
+    }
+}
+
+fn are_lookalikes(a: u8, b: u8) -> bool {
+    let (a, b) = (a.to_ascii_lowercase(), b.to_ascii_lowercase());
+    LOOKALIKES.contains(&(a, b)) || LOOKALIKES.contains(&(b, a))
+}
 
+/// The cost of typing symbol `typed` when `intended` was meant. All encodings
+/// of the symbols are considered, so for example `1` is near `k`, since `l`
+/// is one of the encodings of `1`.
+fn substitution_cost(intended: usize, typed: usize) -> u8 {
+    if intended == typed {
+        return 0;
+    }
+
+    let near = CODE[intended].iter().any(|&a| {
+        CODE[typed]
+            .iter()
+            .any(|&b| are_adjacent_keys(a, b) || are_lookalikes(a, b))
+    });
+
+    if near {
+        NEAR_SUBSTITUTION_COST
+    } else {
+        FAR_SUBSTITUTION_COST
+    }
+}
+
+/// The Crockford base 32 alphabet differs from Id30 in that it excludes `u`
+/// rather than `s`, shifting the encoding of the values 25 and 26, and in that
+/// the canonical encoding is upper case
+fn crockford_code() -> Vec<Vec<u8>> {
+    let mut code: Vec<Vec<u8>> = CODE.iter().map(|encodings| encodings.to_vec()).collect();
+    code[15] = b"fF".to_vec();
+    code[25] = b"sS".to_vec();
+    code[26] = b"tT".to_vec();
+    for encodings in &mut code {
+        if encodings[0].is_ascii_lowercase() {
+            encodings.swap(0, 1);
+        }
+    }
+    code
+}
+
+fn decode_table_for(code: &[impl AsRef<[u8]>]) -> [u8; 256] {
     let mut decode_table = [ERR_FLAG; 256];
-    for (symbol, encoded) in CODE.iter().enumerate() {
-        for (index, encoded) in encoded.iter().enumerate() {
+    for (symbol, encoded) in code.iter().enumerate() {
+        for (index, encoded) in encoded.as_ref().iter().enumerate() {
             let flags = if index == 0 { 0 } else { ALT_FLAG };
             decode_table[*encoded as usize] = symbol as u8 | flags;
         }
     }
+    decode_table
+}
 
-    let mut out = fs::File::create(dest_path).unwrap();
-
-    write!(&mut out, "pub(crate) const ENCODE: [u8; 32] = *b\"").unwrap();
-    for x in &encode_table {
-        write!(&mut out, "{}", char::from(*x)).unwrap();
+fn write_encode_table(out: &mut impl Write, name: &str, table: &[u8]) {
+    write!(out, "pub(crate) const {name}: [u8; 32] = *b\"").unwrap();
+    for x in table {
+        write!(out, "{}", char::from(*x)).unwrap();
     }
-    writeln!(&mut out, "\";\n").unwrap();
+    writeln!(out, "\";\n").unwrap();
+}
 
-    writeln!(&mut out, "pub(crate) const DECODE: [u8; 256] = [").unwrap();
-    for x in decode_table {
+fn write_decode_table(out: &mut impl Write, name: &str, table: &[u8]) {
+    writeln!(out, "pub(crate) const {name}: [u8; {}] = [", table.len()).unwrap();
+    for &x in table {
         if x == ERR_FLAG {
-            write!(&mut out, "    ERR_FLAG").unwrap();
+            write!(out, "    ERR_FLAG").unwrap();
         } else {
-            write!(&mut out, "    {}", x & 0b1_1111).unwrap();
+            write!(out, "    {}", x & 0b1_1111).unwrap();
             if x & ALT_FLAG != 0 {
-                write!(&mut out, " | ALT_FLAG").unwrap();
+                write!(out, " | ALT_FLAG").unwrap();
             }
         }
-        writeln!(&mut out, ",").unwrap();
+        writeln!(out, ",").unwrap();
     }
-    writeln!(&mut out, "];").unwrap();
+    writeln!(out, "];").unwrap();
+}
 
-    writeln!(&mut out, "pub(crate) const DECODE_LOW: [u8; 64] = [").unwrap();
-    for &x in &decode_table[0..64] {
-        if x == ERR_FLAG {
-            write!(&mut out, "    ERR_FLAG").unwrap();
-        } else {
-            write!(&mut out, "    {}", x & 0b1_1111).unwrap();
-            if x & ALT_FLAG != 0 {
-                write!(&mut out, " | ALT_FLAG").unwrap();
-            }
-        }
-        writeln!(&mut out, ",").unwrap();
-    }
-    writeln!(&mut out, "];").unwrap();
+fn main() {
+    let out_dir = env::var_os("OUT_DIR").unwrap();
+    let dest_path = Path::new(&out_dir).join("codec_tables.rs");
 
-    writeln!(&mut out, "pub(crate) const DECODE_HIGH: [u8; 64] = [").unwrap();
-    for &x in &decode_table[64..128] {
-        if x == ERR_FLAG {
-            write!(&mut out, "    ERR_FLAG").unwrap();
-        } else {
-            write!(&mut out, "    {}", x & 0b1_1111).unwrap();
-            if x & ALT_FLAG != 0 {
-                write!(&mut out, " | ALT_FLAG").unwrap();
-            }
+    let encode_table: Vec<_> = CODE.iter().map(|encodings| encodings[0]).collect();
+
+    let decode_table = decode_table_for(&CODE);
+
+    let crockford_code = crockford_code();
+    let crockford_encode_table: Vec<_> = crockford_code
+        .iter()
+        .map(|encodings| encodings[0])
+        .collect();
+    let crockford_decode_table = decode_table_for(&crockford_code);
+
+    let mut out = fs::File::create(dest_path).unwrap();
+
+    write_encode_table(&mut out, "ENCODE", &encode_table);
+    write_decode_table(&mut out, "DECODE", &decode_table);
+    write_decode_table(&mut out, "DECODE_LOW", &decode_table[0..64]);
+    write_decode_table(&mut out, "DECODE_HIGH", &decode_table[64..128]);
+
+    write_encode_table(&mut out, "CROCKFORD_ENCODE", &crockford_encode_table);
+    write_decode_table(&mut out, "CROCKFORD_DECODE", &crockford_decode_table);
+
+    writeln!(
+        &mut out,
+        "pub(crate) const SUBSTITUTION_COST: [[u8; 32]; 32] = ["
+    )
+    .unwrap();
+    for intended in 0..32 {
+        write!(&mut out, "    [").unwrap();
+        for typed in 0..32 {
+            write!(&mut out, "{}, ", substitution_cost(intended, typed)).unwrap();
         }
-        writeln!(&mut out, ",").unwrap();
+        writeln!(&mut out, "],").unwrap();
     }
     writeln!(&mut out, "];").unwrap();
 
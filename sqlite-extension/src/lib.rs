@@ -0,0 +1,221 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A SQLite loadable extension for the Id30 encoding. Build it with `cargo
+//! build --release` in this directory, and load it in the `sqlite3` shell
+//! with `.load ./libid30`, or with `load_extension` from any other SQLite
+//! client. The extension registers the following SQL functions:
+//!
+//!  - `id30_encode(integer)` gives the canonical Id30 string for an integer
+//!  - `id30_decode(text)` gives the integer value of an Id30 string, which may
+//!    be non-canonical
+//!  - `id30_canonicalize(text)` gives the canonical encoding of an Id30 string
+//!  - `id30_random()` gives a random Id30 as an integer
+//!
+//! All the functions give `NULL` for `NULL` input. For input that is invalid,
+//! such as integers that are out of range for Id30 or strings that are not
+//! valid Id30 strings, `id30_encode`, `id30_decode` and `id30_canonicalize`
+//! raise an error. The variants `id30_try_encode`, `id30_try_decode` and
+//! `id30_try_canonicalize` give `NULL` for invalid input instead.
+
+use std::ffi::{c_char, c_int, c_void, CStr};
+
+use id30::{Id30, Id30Parse, ParseError};
+use libsqlite3_sys::{
+    rusqlite_extension_init2, sqlite3, sqlite3_api_routines, sqlite3_context,
+    sqlite3_create_function_v2, sqlite3_randomness, sqlite3_result_error, sqlite3_result_int64,
+    sqlite3_result_null, sqlite3_result_text, sqlite3_value, sqlite3_value_bytes,
+    sqlite3_value_int64, sqlite3_value_text, sqlite3_value_type, SQLITE_DETERMINISTIC,
+    SQLITE_ERROR, SQLITE_INTEGER, SQLITE_NULL, SQLITE_OK, SQLITE_TEXT, SQLITE_TRANSIENT,
+    SQLITE_UTF8,
+};
+
+/// The value of a function argument, when it is either `NULL` or of the
+/// expected type
+enum Arg<T> {
+    Null,
+    Value(T),
+    Invalid(&'static str),
+}
+
+/// The result of a function, when the input was not invalid
+enum Output {
+    Null,
+    Integer(i64),
+    Text(Id30),
+}
+
+unsafe fn integer_arg(value: *mut sqlite3_value) -> Arg<Id30> {
+    match sqlite3_value_type(value) {
+        SQLITE_NULL => Arg::Null,
+        SQLITE_INTEGER => match Id30::try_from(sqlite3_value_int64(value)) {
+            Ok(id30) => Arg::Value(id30),
+            Err(_) => Arg::Invalid("integer out of range for Id30"),
+        },
+        _ => Arg::Invalid("argument must be an integer"),
+    }
+}
+
+unsafe fn text_arg(value: *mut sqlite3_value) -> Arg<Id30Parse> {
+    match sqlite3_value_type(value) {
+        SQLITE_NULL => Arg::Null,
+        SQLITE_TEXT => {
+            // sqlite3_value_bytes must be called after sqlite3_value_text,
+            // since the text conversion may change the length
+            let text = sqlite3_value_text(value);
+            let len = sqlite3_value_bytes(value);
+            let text = std::slice::from_raw_parts(text, len as usize);
+
+            match std::str::from_utf8(text)
+                .map_err(|_| ParseError::InvalidCharacters)
+                .and_then(str::parse)
+            {
+                Ok(parse) => Arg::Value(parse),
+                Err(ParseError::InvalidLength) => Arg::Invalid("invalid length for Id30"),
+                Err(ParseError::InvalidCharacters) => Arg::Invalid("invalid characters for Id30"),
+            }
+        }
+        _ => Arg::Invalid("argument must be text"),
+    }
+}
+
+/// Set the result of the function call from the argument, as mapped by `f`.
+/// Invalid arguments give `NULL` if `TRY` is true, and raise an error
+/// otherwise.
+unsafe fn set_result<const TRY: bool, T>(
+    ctx: *mut sqlite3_context,
+    arg: Arg<T>,
+    f: impl FnOnce(T) -> Output,
+) {
+    let output = match arg {
+        Arg::Null => Output::Null,
+        Arg::Value(value) => f(value),
+        Arg::Invalid(_) if TRY => Output::Null,
+        Arg::Invalid(msg) => {
+            sqlite3_result_error(ctx, msg.as_ptr().cast(), msg.len() as c_int);
+            return;
+        }
+    };
+
+    match output {
+        Output::Null => sqlite3_result_null(ctx),
+        Output::Integer(int) => sqlite3_result_int64(ctx, int),
+        Output::Text(id30) => {
+            let text = id30.to_string();
+            sqlite3_result_text(
+                ctx,
+                text.as_ptr().cast(),
+                text.len() as c_int,
+                SQLITE_TRANSIENT(),
+            );
+        }
+    }
+}
+
+unsafe extern "C" fn encode<const TRY: bool>(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    set_result::<TRY, _>(ctx, integer_arg(*argv), Output::Text);
+}
+
+unsafe extern "C" fn decode<const TRY: bool>(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    set_result::<TRY, _>(ctx, text_arg(*argv), |parse| {
+        Output::Integer(parse.id30.into())
+    });
+}
+
+unsafe extern "C" fn canonicalize<const TRY: bool>(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    set_result::<TRY, _>(ctx, text_arg(*argv), |parse| Output::Text(parse.id30));
+}
+
+unsafe extern "C" fn random(
+    ctx: *mut sqlite3_context,
+    _argc: c_int,
+    _argv: *mut *mut sqlite3_value,
+) {
+    let mut bytes = [0u8; 4];
+    sqlite3_randomness(bytes.len() as c_int, bytes.as_mut_ptr().cast());
+
+    // Use the high bits, like the implementation of rand's Distribution
+    let id30 = Id30::try_from(u32::from_ne_bytes(bytes) >> 2).unwrap();
+    sqlite3_result_int64(ctx, id30.into());
+}
+
+type XFunc = unsafe extern "C" fn(*mut sqlite3_context, c_int, *mut *mut sqlite3_value);
+
+unsafe fn create_function(
+    db: *mut sqlite3,
+    name: &CStr,
+    n_arg: c_int,
+    deterministic: bool,
+    x_func: XFunc,
+) -> c_int {
+    let flags = if deterministic {
+        SQLITE_UTF8 | SQLITE_DETERMINISTIC
+    } else {
+        SQLITE_UTF8
+    };
+
+    sqlite3_create_function_v2(
+        db,
+        name.as_ptr(),
+        n_arg,
+        flags,
+        std::ptr::null_mut::<c_void>(),
+        Some(x_func),
+        None,
+        None,
+        None,
+    )
+}
+
+/// The entry point of the extension. This is the name SQLite looks for by
+/// default, so no entry point must be given when loading the extension.
+///
+/// # Safety
+///
+/// Must only be called by SQLite when loading the extension
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_extension_init(
+    db: *mut sqlite3,
+    _pz_err_msg: *mut *mut c_char,
+    p_api: *mut sqlite3_api_routines,
+) -> c_int {
+    if rusqlite_extension_init2(p_api).is_err() {
+        return SQLITE_ERROR;
+    }
+
+    let functions: [(&CStr, c_int, bool, XFunc); 7] = [
+        (c"id30_encode", 1, true, encode::<false>),
+        (c"id30_try_encode", 1, true, encode::<true>),
+        (c"id30_decode", 1, true, decode::<false>),
+        (c"id30_try_decode", 1, true, decode::<true>),
+        (c"id30_canonicalize", 1, true, canonicalize::<false>),
+        (c"id30_try_canonicalize", 1, true, canonicalize::<true>),
+        (c"id30_random", 0, false, random),
+    ];
+
+    for (name, n_arg, deterministic, x_func) in functions {
+        let rc = create_function(db, name, n_arg, deterministic, x_func);
+        if rc != SQLITE_OK {
+            return rc;
+        }
+    }
+
+    SQLITE_OK
+}
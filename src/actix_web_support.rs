@@ -0,0 +1,214 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "actix-web4")]
+
+use std::future::{ready, Ready};
+
+use actix_web4::{
+    dev::Payload, http::header::LOCATION, http::StatusCode, FromRequest, HttpRequest, HttpResponse,
+    ResponseError,
+};
+
+use crate::{
+    canonical_location::{canonical_location, Id30PathRejection},
+    Id30, Id30Parse,
+};
+
+impl ResponseError for Id30PathRejection {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Id30PathRejection::NotFound => StatusCode::NOT_FOUND,
+            Id30PathRejection::Redirect(_) => StatusCode::PERMANENT_REDIRECT,
+            Id30PathRejection::MissingPathParams => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            Id30PathRejection::NotFound => HttpResponse::NotFound().finish(),
+            Id30PathRejection::Redirect(location) => HttpResponse::PermanentRedirect()
+                .insert_header((LOCATION, location.as_str()))
+                .finish(),
+            Id30PathRejection::MissingPathParams => {
+                HttpResponse::InternalServerError().body(self.to_string())
+            }
+        }
+    }
+}
+
+/// Parse the last path parameter of the matched resource, yielding it along
+/// with the parse
+fn parse_path(req: &HttpRequest) -> Result<(&str, Id30Parse), Id30PathRejection> {
+    let (_, value) = req
+        .match_info()
+        .iter()
+        .last()
+        .ok_or(Id30PathRejection::MissingPathParams)?;
+
+    let parse = value.parse().map_err(|_| Id30PathRejection::NotFound)?;
+    Ok((value, parse))
+}
+
+/// Extracts the last path parameter of the matched resource as an `Id30`.
+///
+/// If the path parameter is not a valid Id30 string, the request is answered
+/// with 404 Not Found. If it is valid, but not in the canonical encoding, the
+/// request is answered with 308 Permanent Redirect to the same URL with the
+/// path segment replaced by the canonical encoding. The query string and all
+/// other path segments are preserved.
+///
+/// ```
+/// # use actix_web4 as actix_web;
+/// use actix_web::{web, App};
+/// use id30::Id30;
+///
+/// async fn user(id: Id30) -> String {
+///     format!("User {id}")
+/// }
+///
+/// let app = App::new().route("/users/{id}", web::get().to(user));
+/// ```
+impl FromRequest for Id30 {
+    type Error = Id30PathRejection;
+    type Future = Ready<Result<Id30, Id30PathRejection>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(parse_path(req).and_then(|(value, parse)| {
+            if parse.is_canonical {
+                return Ok(parse.id30);
+            }
+
+            let uri = req.uri();
+            Err(
+                canonical_location(uri.path(), uri.query(), value, parse.id30)
+                    .map_or(Id30PathRejection::NotFound, Id30PathRejection::Redirect),
+            )
+        }))
+    }
+}
+
+/// Extracts the last path parameter of the matched resource as an
+/// `Id30Parse`.
+///
+/// If the path parameter is not a valid Id30 string, the request is answered
+/// with 404 Not Found. Non-canonical encodings are accepted, so the handler
+/// can decide how to respond to them.
+impl FromRequest for Id30Parse {
+    type Error = Id30PathRejection;
+    type Future = Ready<Result<Id30Parse, Id30PathRejection>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(parse_path(req).map(|(_, parse)| parse))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web4::{
+        body::to_bytes,
+        test::{call_service, init_service, TestRequest},
+        web, App,
+    };
+
+    use super::*;
+
+    async fn id30_handler(id30: Id30) -> String {
+        id30.to_string()
+    }
+
+    async fn id30_parse_handler(parse: Id30Parse) -> String {
+        format!("{} {}", parse.id30, parse.is_canonical)
+    }
+
+    async fn get_response(uri: &str) -> (StatusCode, String) {
+        let app = init_service(
+            App::new()
+                .route("/u/{id}", web::get().to(id30_handler))
+                .route("/u/{user}/posts/{post}", web::get().to(id30_handler))
+                .route("/p/{id}", web::get().to(id30_parse_handler))
+                .service(web::scope("/api").route("/items/{id}/edit", web::get().to(id30_handler)))
+                .route("/", web::get().to(id30_handler)),
+        )
+        .await;
+
+        let response = call_service(&app, TestRequest::get().uri(uri).to_request()).await;
+        let status = response.status();
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .map(|location| location.to_str().unwrap().to_string());
+        let body = to_bytes(response.into_body()).await.unwrap();
+
+        match location {
+            Some(location) => (status, location),
+            None => (status, String::from_utf8(body.to_vec()).unwrap()),
+        }
+    }
+
+    #[actix_web4::rt::test(system = "actix_web4::rt::System")]
+    async fn canonical() {
+        assert_eq!(
+            get_response("/u/bpv3uq").await,
+            (StatusCode::OK, "bpv3uq".to_string())
+        );
+        assert_eq!(
+            get_response("/u/%62pv3uq").await,
+            (StatusCode::OK, "bpv3uq".to_string())
+        );
+    }
+
+    #[actix_web4::rt::test(system = "actix_web4::rt::System")]
+    async fn invalid() {
+        assert_eq!(get_response("/u/bpv3u").await.0, StatusCode::NOT_FOUND);
+        assert_eq!(get_response("/u/bpv3u!").await.0, StatusCode::NOT_FOUND);
+        assert_eq!(get_response("/p/bpv3u").await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web4::rt::test(system = "actix_web4::rt::System")]
+    async fn redirect() {
+        assert_eq!(
+            get_response("/u/BPV3UQ?sort=new&page=2").await,
+            (
+                StatusCode::PERMANENT_REDIRECT,
+                "/u/bpv3uq?sort=new&page=2".to_string()
+            )
+        );
+        assert_eq!(
+            get_response("/u/BPV3UQ/posts/BPV3UQ").await,
+            (
+                StatusCode::PERMANENT_REDIRECT,
+                "/u/BPV3UQ/posts/bpv3uq".to_string()
+            )
+        );
+        assert_eq!(
+            get_response("/api/items/BPV3UQ/edit").await,
+            (
+                StatusCode::PERMANENT_REDIRECT,
+                "/api/items/bpv3uq/edit".to_string()
+            )
+        );
+    }
+
+    #[actix_web4::rt::test(system = "actix_web4::rt::System")]
+    async fn id30_parse() {
+        assert_eq!(
+            get_response("/p/bpv3uq").await,
+            (StatusCode::OK, "bpv3uq true".to_string())
+        );
+        assert_eq!(
+            get_response("/p/BPV3UQ").await,
+            (StatusCode::OK, "bpv3uq false".to_string())
+        );
+    }
+
+    #[actix_web4::rt::test(system = "actix_web4::rt::System")]
+    async fn missing_path_params() {
+        assert_eq!(get_response("/").await.0, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
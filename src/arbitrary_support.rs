@@ -0,0 +1,96 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "arbitrary1")]
+
+use arbitrary1::{size_hint, Arbitrary, Result, Unstructured};
+
+use crate::{
+    codec_tables::{encodings, DECODE},
+    Id30, Id30Parse,
+};
+
+impl<'a> Arbitrary<'a> for Id30 {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Id30(u.int_in_range(0..=(1 << 30) - 1)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u32::size_hint(depth)
+    }
+}
+
+/// `Id30Parse` is generated by parsing an arbitrary valid encoding of an
+/// arbitrary `Id30`, so `is_canonical` is only `false` for values that have
+/// non-canonical encodings
+impl<'a> Arbitrary<'a> for Id30Parse {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let id30 = Id30::arbitrary(u)?;
+        let mut buf = [0u8; 6];
+        for (b, c) in buf.iter_mut().zip(id30.to_string().bytes()) {
+            *b = *u.choose(&encodings()[(DECODE[c as usize] & 0b1_1111) as usize])?;
+        }
+
+        Ok(std::str::from_utf8(&buf)
+            .expect("encodings are all ASCII")
+            .parse()
+            .expect("encodings are all valid"))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(Id30::size_hint(depth), (0, Some(6 * 4)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn id30() {
+        let mut u = Unstructured::new(&[0xff; 4]);
+        assert_eq!(Id30::arbitrary(&mut u).unwrap(), Id30((1 << 30) - 1));
+
+        let mut u = Unstructured::new(&[]);
+        assert_eq!(Id30::arbitrary(&mut u).unwrap(), Id30(0));
+    }
+
+    #[test]
+    fn id30parse_exhausted() {
+        let mut u = Unstructured::new(&[]);
+        let parse = Id30Parse::arbitrary(&mut u).unwrap();
+        assert_eq!(parse.id30, Id30(0));
+        assert!(parse.is_canonical);
+    }
+
+    #[test]
+    fn id30parse() {
+        let mut state = 1u32;
+        let data: Vec<u8> = (0..4096)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                (state >> 16) as u8
+            })
+            .collect();
+        let mut u = Unstructured::new(&data);
+
+        let (mut canonical, mut non_canonical) = (0, 0);
+        while !u.is_empty() {
+            let parse = Id30Parse::arbitrary(&mut u).unwrap();
+            assert!(Id30::try_from(u32::from(parse.id30)).is_ok());
+            if parse.is_canonical {
+                canonical += 1;
+            } else {
+                non_canonical += 1;
+            }
+        }
+
+        assert!(canonical > 0);
+        assert!(non_canonical > 0);
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "axum08")]
+
+use axum08::{
+    extract::{FromRequestParts, OriginalUri, RawPathParams},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+};
+
+use crate::{
+    canonical_location::{canonical_location, Id30PathRejection},
+    Id30, Id30Parse,
+};
+
+/// An axum extractor for an [`Id30`] in the request path, which redirects
+/// requests for non-canonical encodings to the canonical URL.
+///
+/// `Id30Path` extracts the last path parameter of the matched route. If it is
+/// not a valid Id30 string, the request is rejected with 404 Not Found. If it
+/// is valid, but not in the canonical encoding, the request is answered with
+/// 308 Permanent Redirect to the same URL with the path segment replaced by
+/// the canonical encoding. The query string and all other path segments are
+/// preserved. See [`Id30PathRejection`][crate::Id30PathRejection].
+///
+/// ```
+/// # use axum08 as axum;
+/// use axum::{routing::get, Router};
+/// use id30::Id30Path;
+///
+/// async fn user(Id30Path(id): Id30Path) -> String {
+///     format!("User {id}")
+/// }
+///
+/// let app: Router = Router::new().route("/users/{id}", get(user));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Id30Path(pub Id30);
+
+impl IntoResponse for Id30PathRejection {
+    fn into_response(self) -> Response {
+        match self {
+            Id30PathRejection::NotFound => StatusCode::NOT_FOUND.into_response(),
+            Id30PathRejection::Redirect(location) => Redirect::permanent(&location).into_response(),
+            Id30PathRejection::MissingPathParams => {
+                (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+            }
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for Id30Path
+where
+    S: Send + Sync,
+{
+    type Rejection = Id30PathRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let params = RawPathParams::from_request_parts(parts, state)
+            .await
+            .map_err(|_| Id30PathRejection::MissingPathParams)?;
+        let (_, value) = params
+            .iter()
+            .last()
+            .ok_or(Id30PathRejection::MissingPathParams)?;
+
+        let parse: Id30Parse = value.parse().map_err(|_| Id30PathRejection::NotFound)?;
+        if parse.is_canonical {
+            return Ok(Id30Path(parse.id30));
+        }
+
+        let OriginalUri(uri) = match OriginalUri::from_request_parts(parts, state).await {
+            Ok(uri) => uri,
+            Err(infallible) => match infallible {},
+        };
+        Err(
+            canonical_location(uri.path(), uri.query(), value, parse.id30)
+                .map_or(Id30PathRejection::NotFound, Id30PathRejection::Redirect),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use axum08::{
+        body::{to_bytes, Body},
+        http::{header::LOCATION, Request},
+        routing::get,
+        Router,
+    };
+    use tower05::ServiceExt;
+
+    use super::*;
+
+    async fn handler(Id30Path(id30): Id30Path) -> String {
+        id30.to_string()
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/u/{id}", get(handler))
+            .route("/u/{user}/posts/{post}", get(handler))
+            .nest(
+                "/api",
+                Router::new().route("/items/{id}/edit", get(handler)),
+            )
+    }
+
+    async fn get_response(uri: &str) -> (StatusCode, String) {
+        let response = app()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .map(|location| location.to_str().unwrap().to_string());
+        let body = to_bytes(response.into_body(), 1024).await.unwrap();
+
+        match location {
+            Some(location) => (status, location),
+            None => (status, String::from_utf8(body.to_vec()).unwrap()),
+        }
+    }
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn canonical() {
+        assert_eq!(
+            get_response("/u/bpv3uq").await,
+            (StatusCode::OK, "bpv3uq".to_string())
+        );
+        assert_eq!(
+            get_response("/u/%62pv3uq").await,
+            (StatusCode::OK, "bpv3uq".to_string())
+        );
+    }
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn invalid() {
+        assert_eq!(get_response("/u/bpv3u").await.0, StatusCode::NOT_FOUND);
+        assert_eq!(get_response("/u/bpv3u!").await.0, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn redirect() {
+        assert_eq!(
+            get_response("/u/BPV3UQ").await,
+            (StatusCode::PERMANENT_REDIRECT, "/u/bpv3uq".to_string())
+        );
+        assert_eq!(
+            get_response("/u/BPV3UQ?sort=new&page=2").await,
+            (
+                StatusCode::PERMANENT_REDIRECT,
+                "/u/bpv3uq?sort=new&page=2".to_string()
+            )
+        );
+    }
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn redirect_preserves_other_segments() {
+        assert_eq!(
+            get_response("/u/Alice/posts/BPV3UQ").await,
+            (
+                StatusCode::PERMANENT_REDIRECT,
+                "/u/Alice/posts/bpv3uq".to_string()
+            )
+        );
+        assert_eq!(
+            get_response("/u/BPV3UQ/posts/BPV3UQ").await,
+            (
+                StatusCode::PERMANENT_REDIRECT,
+                "/u/BPV3UQ/posts/bpv3uq".to_string()
+            )
+        );
+        assert_eq!(
+            get_response("/api/items/BPV3UQ/edit?x=1").await,
+            (
+                StatusCode::PERMANENT_REDIRECT,
+                "/api/items/bpv3uq/edit?x=1".to_string()
+            )
+        );
+    }
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn missing_path_params() {
+        let app: Router = Router::new().route("/", get(handler));
+        let response = app
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -8,7 +8,7 @@
 
 use std::process::ExitCode;
 
-use id30::Id30;
+use id30::{Id30, SqlDialect};
 use rand08::prelude::*;
 
 fn help() -> ExitCode {
@@ -17,6 +17,7 @@ fn help() -> ExitCode {
 Generate and interrogate id30 identifiers.
 
 USAGE: id30 [--help] [--json] {{ID}}
+       id30 sql {{DIALECT}}
 
 --help   Show this help
 --json   Format the output as JSON
@@ -27,6 +28,10 @@ USAGE: id30 [--help] [--json] {{ID}}
 
          If no IDs are given, one is generated randomly and printed out in both
          id30 and integer formats.
+
+sql      Print SQL for working with Id30 values in the database, such as
+         id30_encode and id30_decode functions, for the given {{DIALECT}}, one
+         of postgres, mysql or sqlite.
 "
     );
     ExitCode::SUCCESS
@@ -37,6 +42,25 @@ fn unknown_option(option: &str) -> ExitCode {
     ExitCode::FAILURE
 }
 
+fn sql(args: &[String]) -> ExitCode {
+    match args {
+        [dialect] => match dialect.parse::<SqlDialect>() {
+            Ok(dialect) => {
+                print!("{}", dialect.schema());
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("{err}");
+                ExitCode::FAILURE
+            }
+        },
+        _ => {
+            eprintln!("Usage: id30 sql {{DIALECT}}, try --help");
+            ExitCode::FAILURE
+        }
+    }
+}
+
 fn report(id30: Id30, json: bool) {
     if !json {
         println!("Id30: {id30}\nu32: {}", u32::from(id30));
@@ -50,7 +74,12 @@ fn main() -> ExitCode {
     let mut json = false;
     let mut query_ids = vec![];
 
-    for arg in std::env::args().skip(1) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(("sql", args)) = args.split_first().map(|(cmd, args)| (cmd.as_str(), args)) {
+        return sql(args);
+    }
+
+    for arg in args {
         match arg.as_str() {
             "--json" => json = true,
             "--help" => return help(),
@@ -0,0 +1,83 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "bincode2")]
+
+use bincode2::{
+    de::Decoder,
+    enc::Encoder,
+    error::{DecodeError, EncodeError},
+    impl_borrow_decode, Decode, Encode,
+};
+
+use crate::Id30;
+
+/// `Id30` is encoded as `u32`, which is a varint or 4 bytes, depending on the
+/// integer encoding of the bincode configuration
+impl Encode for Id30 {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.0.encode(encoder)
+    }
+}
+
+impl<Context> Decode<Context> for Id30 {
+    fn decode<D: Decoder<Context = Context>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        Id30::try_from(u32::decode(decoder)?)
+            .map_err(|err| DecodeError::OtherString(err.to_string()))
+    }
+}
+
+impl_borrow_decode!(Id30);
+
+#[cfg(test)]
+mod test {
+    use bincode2::config;
+
+    use super::*;
+
+    #[test]
+    fn varint() {
+        let id30 = Id30::try_from(0x3fff_ffff).unwrap();
+        let bytes = bincode2::encode_to_vec(id30, config::standard()).unwrap();
+        assert_eq!(bytes, [0xfc, 0xff, 0xff, 0xff, 0x3f]);
+        assert_eq!(
+            bincode2::decode_from_slice(&bytes, config::standard()).unwrap(),
+            (id30, bytes.len())
+        );
+
+        let id30 = Id30::try_from(7).unwrap();
+        let bytes = bincode2::encode_to_vec(id30, config::standard()).unwrap();
+        assert_eq!(bytes, [7]);
+    }
+
+    #[test]
+    fn fixed() {
+        let config = config::standard().with_fixed_int_encoding();
+        let id30 = Id30::try_from(0x1234_5678).unwrap();
+        let bytes = bincode2::encode_to_vec(id30, config).unwrap();
+        assert_eq!(bytes, [0x78, 0x56, 0x34, 0x12]);
+        assert_eq!(
+            bincode2::decode_from_slice(&bytes, config).unwrap(),
+            (id30, 4)
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        let config = config::standard().with_fixed_int_encoding();
+        let bytes = 0x4000_0000u32.to_le_bytes();
+        let res: Result<(Id30, usize), _> = bincode2::decode_from_slice(&bytes, config);
+        assert!(matches!(
+            res,
+            Err(DecodeError::OtherString(msg)) if msg == "value out of range for Id30"
+        ));
+
+        let res: Result<(Id30, usize), _> = bincode2::borrow_decode_from_slice(&bytes, config);
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "borsh1")]
+
+use std::io;
+
+use borsh1::{BorshDeserialize, BorshSerialize};
+
+use crate::Id30;
+
+/// `Id30` is serialized as `u32`, ie 4 bytes in little endian
+impl BorshSerialize for Id30 {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.0.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for Id30 {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        Id30::try_from(u32::deserialize_reader(reader)?)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::OutOfRangeError;
+
+    #[test]
+    fn roundtrip() {
+        let id30 = Id30::try_from(0x1234_5678).unwrap();
+        let bytes = borsh1::to_vec(&id30).unwrap();
+        assert_eq!(bytes, [0x78, 0x56, 0x34, 0x12]);
+        assert_eq!(borsh1::from_slice::<Id30>(&bytes).unwrap(), id30);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let err = borsh1::from_slice::<Id30>(&0x4000_0000u32.to_le_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.into_inner().unwrap().is::<OutOfRangeError>());
+    }
+
+    #[test]
+    fn too_short() {
+        assert!(borsh1::from_slice::<Id30>(&[0x78, 0x56, 0x34]).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::{fmt, str::FromStr};
+
+#[cfg(feature = "diesel2")]
+use diesel2::sql_types::Text;
+
+use crate::{Id30, Id30Parse, ParseError};
+
+/// An [`Id30`] that only accepts the canonical encoding when parsed.
+///
+/// Parsing `Id30` accepts any valid encoding, and parsing [`Id30Parse`]
+/// additionally reports whether the encoding was canonical. Parsing
+/// `CanonicalId30` rejects non-canonical encodings with
+/// [`CanonicalParseError::NonCanonical`], for input where a non-canonical
+/// encoding indicates a bug in whatever produced it. With feature `serde`,
+/// this also applies to deserialization, see also
+/// [`id30::serde::canonical`](crate::serde::canonical).
+///
+/// ```
+/// # use id30::{CanonicalId30, CanonicalParseError};
+/// let CanonicalId30(id) = "bpv3uq".parse().unwrap();
+/// assert_eq!(
+///     "BPV3UQ".parse::<CanonicalId30>(),
+///     Err(CanonicalParseError::NonCanonical(id))
+/// );
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+#[cfg_attr(feature = "diesel2", derive(diesel::AsExpression, diesel::FromSqlRow))]
+#[cfg_attr(feature = "diesel2", diesel(sql_type = Text))]
+pub struct CanonicalId30(pub Id30);
+
+/// The given string was not a canonical Id30 string
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CanonicalParseError {
+    /// The given string was not a valid Id30 string
+    Invalid(ParseError),
+
+    /// The given string was a valid, but non-canonical, encoding of the
+    /// contained `Id30`
+    NonCanonical(Id30),
+}
+
+impl fmt::Display for CanonicalParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CanonicalParseError::Invalid(err) => err.fmt(fmt),
+            CanonicalParseError::NonCanonical(id30) => write!(
+                fmt,
+                "non-canonical encoding of Id30, the canonical encoding is {id30}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CanonicalParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CanonicalParseError::Invalid(err) => Some(err),
+            CanonicalParseError::NonCanonical(_) => None,
+        }
+    }
+}
+
+impl TryFrom<Id30Parse> for CanonicalId30 {
+    type Error = CanonicalParseError;
+
+    fn try_from(parse: Id30Parse) -> Result<Self, Self::Error> {
+        if parse.is_canonical {
+            Ok(CanonicalId30(parse.id30))
+        } else {
+            Err(CanonicalParseError::NonCanonical(parse.id30))
+        }
+    }
+}
+
+impl FromStr for CanonicalId30 {
+    type Err = CanonicalParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<Id30Parse>()
+            .map_err(CanonicalParseError::Invalid)?
+            .try_into()
+    }
+}
+
+impl fmt::Display for CanonicalId30 {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(fmt)
+    }
+}
+
+impl From<Id30> for CanonicalId30 {
+    fn from(id30: Id30) -> CanonicalId30 {
+        CanonicalId30(id30)
+    }
+}
+
+impl From<CanonicalId30> for Id30 {
+    fn from(canonical: CanonicalId30) -> Id30 {
+        canonical.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        assert_eq!("bpv3uq".parse(), Ok(CanonicalId30(id30)));
+        assert_eq!(
+            "bpv3Uq".parse::<CanonicalId30>(),
+            Err(CanonicalParseError::NonCanonical(id30))
+        );
+        assert_eq!(
+            "bpv3u".parse::<CanonicalId30>(),
+            Err(CanonicalParseError::Invalid(ParseError::InvalidLength))
+        );
+    }
+
+    #[test]
+    fn display() {
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        assert_eq!(CanonicalId30(id30).to_string(), "bpv3uq");
+        assert_eq!(
+            CanonicalParseError::NonCanonical(id30).to_string(),
+            "non-canonical encoding of Id30, the canonical encoding is bpv3uq"
+        );
+    }
+}
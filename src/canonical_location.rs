@@ -0,0 +1,110 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(any(feature = "axum08", feature = "actix-web4"))]
+
+//! Helpers for redirecting requests for non-canonical Id30 path segments in
+//! the web framework integrations
+
+use std::fmt;
+
+use crate::{percent_decode::percent_decode, Id30};
+
+/// The reason for rejecting a request with an Id30 in the path, in the
+/// integrations with web frameworks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Id30PathRejection {
+    /// The path parameter is not a valid Id30 string. Responds with 404 Not
+    /// Found.
+    NotFound,
+
+    /// The path parameter is not in the canonical encoding. Responds with 308
+    /// Permanent Redirect to the contained location.
+    Redirect(String),
+
+    /// The matched route has no path parameters, which is a programming error.
+    /// Responds with 500 Internal Server Error.
+    MissingPathParams,
+}
+
+impl fmt::Display for Id30PathRejection {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Id30PathRejection::NotFound => "invalid Id30 in path".fmt(fmt),
+            Id30PathRejection::Redirect(location) => {
+                write!(
+                    fmt,
+                    "non-canonical Id30 in path, canonical URL is {location}"
+                )
+            }
+            Id30PathRejection::MissingPathParams => {
+                "no path parameters found for matched route".fmt(fmt)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Id30PathRejection {}
+
+/// `true` iff the percent-decoding of `segment` is `value`
+fn decodes_to(segment: &str, value: &str) -> bool {
+    percent_decode(segment).is_some_and(|decoded| decoded == value)
+}
+
+/// Build the URL for `path` and `query` with the last path segment that
+/// decodes to `value` replaced by `id30`
+pub(crate) fn canonical_location(
+    path: &str,
+    query: Option<&str>,
+    value: &str,
+    id30: Id30,
+) -> Option<String> {
+    let mut segments: Vec<&str> = path.split('/').collect();
+    let segment = segments
+        .iter_mut()
+        .rev()
+        .find(|segment| decodes_to(segment, value))?;
+
+    let canonical = id30.to_string();
+    *segment = &canonical;
+
+    let mut location = segments.join("/");
+    if let Some(query) = query {
+        location.push('?');
+        location.push_str(query);
+    }
+    Some(location)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decoding() {
+        assert!(decodes_to("bpv3uq", "bpv3uq"));
+        assert!(decodes_to("%62%70v3uq", "bpv3uq"));
+        assert!(!decodes_to("bpv3uq", "bpv3u"));
+        assert!(!decodes_to("bpv3u", "bpv3uq"));
+        assert!(!decodes_to("%6", "b"));
+    }
+
+    #[test]
+    fn location() {
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        assert_eq!(
+            canonical_location("/a/BPV3UQ/b/BPV3UQ", Some("x=1"), "BPV3UQ", id30),
+            Some("/a/BPV3UQ/b/bpv3uq?x=1".to_string())
+        );
+        assert_eq!(
+            canonical_location("/a/%42PV3UQ/b", None, "BPV3UQ", id30),
+            Some("/a/bpv3uq/b".to_string())
+        );
+        assert_eq!(canonical_location("/a/b", None, "BPV3UQ", id30), None);
+    }
+}
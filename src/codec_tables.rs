@@ -30,3 +30,18 @@ pub(crate) const ERR_MASK: u64 = (ERR_FLAG as u64)
     | ((ERR_FLAG as u64) << 56);
 
 include!(concat!(env!("OUT_DIR"), "/codec_tables.rs"));
+
+/// All the characters that decode to each symbol, with the canonical encoding
+/// first, derived from the decode table and computed once
+pub(crate) fn encodings() -> &'static [Vec<u8>] {
+    static ENCODINGS: std::sync::OnceLock<Vec<Vec<u8>>> = std::sync::OnceLock::new();
+    ENCODINGS.get_or_init(|| {
+        (0..32u8)
+            .map(|symbol| {
+                let mut encodings = vec![ENCODE[symbol as usize]];
+                encodings.extend((0..=255u8).filter(|&c| DECODE[c as usize] == symbol | ALT_FLAG));
+                encodings
+            })
+            .collect()
+    })
+}
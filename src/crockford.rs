@@ -0,0 +1,425 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    codec_tables::{ALT_MASK, CROCKFORD_DECODE, CROCKFORD_ENCODE, DECODE, ERR_MASK},
+    Id30, Id30Parse, ParseError,
+};
+
+fn decode(table: &[u8; 256], s: &str) -> Result<Id30Parse, ParseError> {
+    let s: &[u8; 6] = s
+        .as_bytes()
+        .try_into()
+        .map_err(|_| ParseError::InvalidLength)?;
+
+    let mut decoded = [0u8; 8];
+    decoded
+        .iter_mut()
+        .zip(s)
+        .for_each(|(b, i)| *b = table[*i as usize]);
+
+    let dec_u64 = u64::from_le_bytes(decoded);
+
+    if dec_u64 & ERR_MASK != 0 {
+        return Err(ParseError::InvalidCharacters);
+    }
+
+    let is_canonical = dec_u64 & ALT_MASK == 0;
+
+    let value = decoded
+        .iter()
+        .zip([25, 20, 15, 10, 5, 0])
+        .take(6)
+        .map(|(b, shift)| ((b & 0b1_1111) as u32) << shift)
+        .fold(0, |a, b| a | b);
+
+    Ok(Id30Parse {
+        id30: Id30(value),
+        is_canonical,
+    })
+}
+
+fn with_crockford_str<T>(id30: &Id30, f: impl FnOnce(&str) -> T) -> T {
+    let mut buf = [0u8; 6];
+    buf.iter_mut()
+        .zip([25, 20, 15, 10, 5, 0])
+        .for_each(|(b, shift)| *b = CROCKFORD_ENCODE[((id30.0 >> shift) & 0b1_1111) as usize]);
+
+    f(std::str::from_utf8(&buf).expect("CROCKFORD_ENCODE is all ASCII"))
+}
+
+impl Id30 {
+    /// Render the Id30 in Crockford's base 32 encoding, as six upper case
+    /// characters.
+    ///
+    /// Crockford's alphabet is the same as the Id30 alphabet, except that it
+    /// excludes `u` rather than `s`. The two encodings of the same value
+    /// therefore only differ in the characters for the values 25 and 26,
+    /// which are `t` and `u` in Id30 and `S` and `T` in Crockford's encoding.
+    ///
+    /// ```
+    /// # use id30::Id30;
+    /// let id: Id30 = "bpv3uq".parse().unwrap();
+    /// assert_eq!(id.to_crockford(), "BPV3TQ");
+    /// ```
+    pub fn to_crockford(&self) -> String {
+        with_crockford_str(self, str::to_string)
+    }
+
+    /// Parse the Crockford base 32 encoding of an Id30, as generated by
+    /// [`Id30::to_crockford`].
+    ///
+    /// See [`Id30Parse::from_crockford`] for details about accepted input.
+    pub fn from_crockford(s: &str) -> Result<Id30, ParseError> {
+        Id30Parse::from_crockford(s).map(|x| x.id30)
+    }
+}
+
+impl Id30Parse {
+    /// Parse the Crockford base 32 encoding of an Id30, as generated by
+    /// [`Id30::to_crockford`].
+    ///
+    /// Decoding is case-insensitive and maps the confusable characters `o` to
+    /// 0 and `i` and `l` to 1, like Id30 decoding. The input is canonical when
+    /// it is in upper case and contains no confusable characters.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), id30::ParseError> {
+    /// use id30::Id30Parse;
+    ///
+    /// let parse = Id30Parse::from_crockford("BPV3TQ")?;
+    /// assert_eq!(&parse.id30.to_string(), "bpv3uq");
+    /// assert!(parse.is_canonical);
+    ///
+    /// let parse = Id30Parse::from_crockford("bpv3tq")?;
+    /// assert_eq!(&parse.id30.to_string(), "bpv3uq");
+    /// assert!(!parse.is_canonical);
+    /// # Ok(())}
+    /// ```
+    pub fn from_crockford(s: &str) -> Result<Id30Parse, ParseError> {
+        decode(&CROCKFORD_DECODE, s)
+    }
+}
+
+/// The result of parsing a string that may be in either the Id30 encoding or
+/// Crockford's base 32 encoding, see [`DetectedAlphabet::detect`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum DetectedAlphabet {
+    /// The string is only valid in the Id30 encoding, because it contains `u`
+    Id30(Id30Parse),
+
+    /// The string is only valid in Crockford's encoding. Since Id30 decoding
+    /// accepts `s` as a confusable of `f`, every valid Crockford string is
+    /// also a valid Id30 string, so this variant is only used for strings
+    /// that are in Crockford's canonical form, but not in Id30's.
+    Crockford(Id30Parse),
+
+    /// The string is valid in both encodings, possibly with different values
+    Both {
+        /// The string parsed as an Id30 string
+        id30: Id30Parse,
+
+        /// The string parsed as a Crockford string
+        crockford: Id30Parse,
+    },
+}
+
+impl DetectedAlphabet {
+    /// Parse `s` in both the Id30 encoding and Crockford's base 32 encoding,
+    /// and detect which of them it is in.
+    ///
+    /// Strings that contain `u` can only be in the Id30 encoding. Strings in
+    /// upper case that contain `S` or `T` are taken to be in Crockford's
+    /// canonical encoding, since an Id30 string would not use the confusable
+    /// `S` and is canonically lower case. All other strings that are valid in
+    /// both are reported as [`DetectedAlphabet::Both`], and they decode to the
+    /// same value in both encodings unless they contain `s` or `t`.
+    ///
+    /// If `s` is valid in neither encoding, this yields the error from
+    /// parsing it as Id30.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), id30::ParseError> {
+    /// use id30::DetectedAlphabet;
+    ///
+    /// let detected = DetectedAlphabet::detect("bpv3uq")?;
+    /// assert!(matches!(detected, DetectedAlphabet::Id30(_)));
+    ///
+    /// let detected = DetectedAlphabet::detect("BPV3TQ")?;
+    /// assert!(matches!(detected, DetectedAlphabet::Crockford(_)));
+    ///
+    /// let detected = DetectedAlphabet::detect("zvaec2")?;
+    /// assert!(matches!(detected, DetectedAlphabet::Both { .. }));
+    /// assert_eq!(&detected.id30().unwrap().to_string(), "zvaec2");
+    ///
+    /// let detected = DetectedAlphabet::detect("bpv3tq")?;
+    /// assert!(matches!(detected, DetectedAlphabet::Both { .. }));
+    /// assert_eq!(detected.id30(), None);
+    /// # Ok(())}
+    /// ```
+    pub fn detect(s: &str) -> Result<DetectedAlphabet, ParseError> {
+        let id30 = decode(&DECODE, s);
+        let crockford = decode(&CROCKFORD_DECODE, s);
+
+        match (id30, crockford) {
+            (Ok(id30), Err(_)) => Ok(DetectedAlphabet::Id30(id30)),
+            (Err(_), Ok(crockford)) => Ok(DetectedAlphabet::Crockford(crockford)),
+            (Ok(id30), Ok(crockford)) => {
+                if crockford.is_canonical && !id30.is_canonical && s.contains(['S', 'T']) {
+                    Ok(DetectedAlphabet::Crockford(crockford))
+                } else {
+                    Ok(DetectedAlphabet::Both { id30, crockford })
+                }
+            }
+            (Err(err), Err(_)) => Err(err),
+        }
+    }
+
+    /// The decoded value, if it is the same regardless of which encoding the
+    /// string is in
+    pub fn id30(&self) -> Option<Id30> {
+        match self {
+            DetectedAlphabet::Id30(parse) | DetectedAlphabet::Crockford(parse) => Some(parse.id30),
+            DetectedAlphabet::Both { id30, crockford } => {
+                (id30.id30 == crockford.id30).then_some(id30.id30)
+            }
+        }
+    }
+}
+
+impl FromStr for DetectedAlphabet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DetectedAlphabet::detect(s)
+    }
+}
+
+/// An adapter for an [`Id30`] that is formatted, parsed and serialized in
+/// Crockford's base 32 encoding.
+///
+/// `Crockford` implements the same traits as `Id30` for conversion to and from
+/// text, but using [`Id30::to_crockford`] and [`Id30Parse::from_crockford`].
+/// With feature `rand`, random values can be generated directly as
+/// `Crockford`, and with feature `serde`, it serializes to and deserializes
+/// from the Crockford encoding. See also [`id30::serde::crockford`] for
+/// serializing `Id30` fields directly.
+///
+/// ```
+/// # use id30::{Crockford, Id30};
+/// let id: Id30 = "bpv3uq".parse().unwrap();
+/// assert_eq!(&Crockford(id).to_string(), "BPV3TQ");
+///
+/// let Crockford(parsed) = "bpv3tq".parse().unwrap();
+/// assert_eq!(parsed, id);
+/// ```
+///
+/// [`id30::serde::crockford`]: crate::serde::crockford
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct Crockford(pub Id30);
+
+impl fmt::Display for Crockford {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        with_crockford_str(&self.0, |s| s.fmt(fmt))
+    }
+}
+
+impl FromStr for Crockford {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Id30::from_crockford(s).map(Crockford)
+    }
+}
+
+impl From<Id30> for Crockford {
+    fn from(id30: Id30) -> Crockford {
+        Crockford(id30)
+    }
+}
+
+impl From<Crockford> for Id30 {
+    fn from(crockford: Crockford) -> Id30 {
+        crockford.0
+    }
+}
+
+#[cfg(feature = "rand08")]
+mod rand08_impl {
+    use rand08::{
+        distributions::{Distribution, Standard},
+        Rng,
+    };
+
+    use super::Crockford;
+
+    impl Distribution<Crockford> for Standard {
+        #[inline]
+        fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Crockford {
+            Crockford(rng.gen())
+        }
+    }
+}
+
+#[cfg(feature = "serde1")]
+mod serde1_impl {
+    use serde1::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{with_crockford_str, Crockford};
+
+    impl Serialize for Crockford {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            with_crockford_str(&self.0, move |s| serializer.serialize_str(s))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Crockford {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            crate::serde::crockford::deserialize(deserializer).map(Crockford)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn to_crockford() {
+        assert_eq!(Id30(0).to_crockford(), "000000");
+        assert_eq!(Id30((1 << 30) - 1).to_crockford(), "ZZZZZZ");
+        assert_eq!(
+            "0123456789abcdefghjkmnpqrtuvwxyz"
+                .as_bytes()
+                .chunks(6)
+                .filter(|chunk| chunk.len() == 6)
+                .map(|chunk| std::str::from_utf8(chunk).unwrap().parse::<Id30>())
+                .map(|id30| id30.unwrap().to_crockford())
+                .collect::<Vec<_>>(),
+            ["012345", "6789AB", "CDEFGH", "JKMNPQ", "RSTVWX"]
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        for i in (0..1 << 30).step_by(999_983) {
+            let id30 = Id30(i);
+            assert_eq!(
+                Id30Parse::from_crockford(&id30.to_crockford()),
+                Ok(Id30Parse {
+                    id30,
+                    is_canonical: true
+                })
+            );
+            assert_eq!(Crockford(id30).to_string(), id30.to_crockford());
+        }
+    }
+
+    #[test]
+    fn non_canonical() {
+        let expected = Id30::from_crockford("0115T0").unwrap();
+        for input in ["0115t0", "OIL5T0", "oil5t0"] {
+            assert_eq!(
+                Id30Parse::from_crockford(input),
+                Ok(Id30Parse {
+                    id30: expected,
+                    is_canonical: false
+                }),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Id30Parse::from_crockford("BPV3UQ"),
+            Err(ParseError::InvalidCharacters)
+        );
+        assert_eq!(
+            Id30Parse::from_crockford("BPV3T"),
+            Err(ParseError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn detect() {
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+
+        assert_eq!(
+            DetectedAlphabet::detect("bpv3uq"),
+            Ok(DetectedAlphabet::Id30(Id30Parse {
+                id30,
+                is_canonical: true
+            }))
+        );
+        assert_eq!(
+            DetectedAlphabet::detect("BPV3TQ"),
+            Ok(DetectedAlphabet::Crockford(Id30Parse {
+                id30,
+                is_canonical: true
+            }))
+        );
+        assert_eq!(
+            DetectedAlphabet::detect("bpv3tq"),
+            Ok(DetectedAlphabet::Both {
+                id30: "bpv3tq".parse().unwrap(),
+                crockford: Id30Parse {
+                    id30,
+                    is_canonical: false
+                },
+            })
+        );
+        assert_eq!(DetectedAlphabet::detect("bpv3tq").unwrap().id30(), None);
+
+        let both = DetectedAlphabet::detect("RFMBYZ").unwrap();
+        assert!(matches!(both, DetectedAlphabet::Both { .. }));
+        assert_eq!(both.id30(), Some("rfmbyz".parse().unwrap()));
+
+        assert_eq!(
+            DetectedAlphabet::detect("bpv3!q"),
+            Err(ParseError::InvalidCharacters)
+        );
+        assert_eq!(
+            DetectedAlphabet::detect("bpv3q"),
+            Err(ParseError::InvalidLength)
+        );
+    }
+
+    #[cfg(feature = "rand08")]
+    #[test]
+    fn rand() {
+        use rand08::{rngs::mock::StepRng, Rng};
+
+        let mut rng = StepRng::new(0x1234_5678, 0);
+        let Crockford(id30) = rng.gen();
+        assert_eq!(id30, StepRng::new(0x1234_5678, 0).gen());
+    }
+
+    #[cfg(feature = "serde1")]
+    #[test]
+    fn serde() {
+        use serde_test1::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
+
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        assert_tokens(&Crockford(id30), &[Token::Str("BPV3TQ")]);
+        assert_de_tokens(&Crockford(id30), &[Token::Str("bpv3tq")]);
+        assert_de_tokens_error::<Crockford>(
+            &[Token::Str("bpv3uq")],
+            "invalid value: string \"bpv3uq\", expected a valid Crockford base 32 string",
+        );
+    }
+}
@@ -12,10 +12,23 @@ use diesel2::{
     backend::Backend,
     deserialize::{self, FromSql},
     serialize::{self, Output, ToSql},
-    sql_types::Integer,
+    sql_types::{BigInt, Integer, Text},
+};
+#[cfg(feature = "rand08")]
+use diesel2::{
+    connection::Connection,
+    query_builder::InsertStatement,
+    query_dsl::methods::ExecuteDsl,
+    result::{DatabaseErrorKind, Error, QueryResult},
+    Insertable, RunQueryDsl, Table,
 };
 
-use crate::Id30;
+#[cfg(feature = "diesel2_mysql")]
+use diesel2::sql_types::Unsigned;
+
+#[cfg(any(feature = "diesel2_postgres", feature = "diesel2_mysql"))]
+use crate::display::generic::with_str;
+use crate::{CanonicalId30, Id30, Id30Parse};
 
 impl<DB> ToSql<Integer, DB> for Id30
 where
@@ -41,6 +54,215 @@ where
     }
 }
 
+impl<DB> FromSql<BigInt, DB> for Id30
+where
+    DB: Backend,
+    i64: FromSql<BigInt, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let num = i64::from_sql(bytes)?;
+        Ok(Id30::try_from(num)?)
+    }
+}
+
+#[cfg(feature = "diesel2_postgres")]
+impl ToSql<BigInt, diesel2::pg::Pg> for Id30 {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, diesel2::pg::Pg>) -> serialize::Result {
+        ToSql::<BigInt, _>::to_sql(&i64::from(*self), &mut out.reborrow())
+    }
+}
+
+#[cfg(feature = "diesel2_mysql")]
+impl ToSql<BigInt, diesel2::mysql::Mysql> for Id30 {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, diesel2::mysql::Mysql>) -> serialize::Result {
+        ToSql::<BigInt, _>::to_sql(&i64::from(*self), &mut out.reborrow())
+    }
+}
+
+#[cfg(feature = "diesel2_sqlite")]
+impl ToSql<BigInt, diesel2::sqlite::Sqlite> for Id30 {
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut Output<'b, '_, diesel2::sqlite::Sqlite>,
+    ) -> serialize::Result {
+        out.set_value(i64::from(*self));
+        Ok(serialize::IsNull::No)
+    }
+}
+
+#[cfg(feature = "diesel2_mysql")]
+impl<DB> ToSql<Unsigned<Integer>, DB> for Id30
+where
+    DB: Backend,
+    u32: ToSql<Unsigned<Integer>, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        ToSql::<Unsigned<Integer>, DB>::to_sql(&self.0, out)
+    }
+}
+
+#[cfg(feature = "diesel2_mysql")]
+impl<DB> FromSql<Unsigned<Integer>, DB> for Id30
+where
+    DB: Backend,
+    u32: FromSql<Unsigned<Integer>, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let num = u32::from_sql(bytes)?;
+        Ok(Id30::try_from(num)?)
+    }
+}
+
+/// Writes the canonical encoding
+#[cfg(feature = "diesel2_postgres")]
+impl ToSql<Text, diesel2::pg::Pg> for Id30 {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, diesel2::pg::Pg>) -> serialize::Result {
+        with_str(self, |s| ToSql::<Text, _>::to_sql(s, &mut out.reborrow()))
+    }
+}
+
+/// Writes the canonical encoding
+#[cfg(feature = "diesel2_mysql")]
+impl ToSql<Text, diesel2::mysql::Mysql> for Id30 {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, diesel2::mysql::Mysql>) -> serialize::Result {
+        with_str(self, |s| ToSql::<Text, _>::to_sql(s, &mut out.reborrow()))
+    }
+}
+
+/// Writes the canonical encoding
+#[cfg(feature = "diesel2_sqlite")]
+impl ToSql<Text, diesel2::sqlite::Sqlite> for Id30 {
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut Output<'b, '_, diesel2::sqlite::Sqlite>,
+    ) -> serialize::Result {
+        out.set_value(self.to_string());
+        Ok(serialize::IsNull::No)
+    }
+}
+
+/// Accepts non-canonical encodings
+impl<DB> FromSql<Text, DB> for Id30
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let parse: Id30Parse = FromSql::<Text, DB>::from_sql(bytes)?;
+        Ok(parse.id30)
+    }
+}
+
+/// Accepts non-canonical encodings and reports them via `is_canonical`
+impl<DB> FromSql<Text, DB> for Id30Parse
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let s = String::from_sql(bytes)?;
+        Ok(s.parse::<Id30Parse>()?)
+    }
+}
+
+/// Writes the canonical encoding
+impl<DB> ToSql<Text, DB> for CanonicalId30
+where
+    DB: Backend,
+    Id30: ToSql<Text, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        ToSql::<Text, DB>::to_sql(&self.0, out)
+    }
+}
+
+/// Rejects non-canonical encodings
+impl<DB> FromSql<Text, DB> for CanonicalId30
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let parse: Id30Parse = FromSql::<Text, DB>::from_sql(bytes)?;
+        Ok(CanonicalId30::try_from(parse)?)
+    }
+}
+
+/// Insert a row with a random `Id30` as its primary key, retrying with a new
+/// random `Id30` when the insert fails with a unique constraint violation.
+///
+/// `values` is called with a fresh `Id30` for each attempt, and must build the
+/// row to insert into `table`. At most `max_attempts` inserts are attempted,
+/// but always at least one. On success, the `Id30` of the inserted row is
+/// returned. Other errors are returned immediately, while the unique
+/// constraint violation of the last attempt is returned if all attempts fail.
+///
+/// Any unique constraint violation causes a retry, so a violation of a
+/// unique constraint on another column is retried until `max_attempts` is
+/// reached. On Postgres, a failed statement aborts the surrounding
+/// transaction, so when calling this inside a transaction, wrap the call in a
+/// nested transaction to make the retries possible.
+///
+/// ```
+/// # extern crate diesel2 as diesel;
+/// # use rand08 as rand;
+/// use diesel::prelude::*;
+///
+/// diesel::table! {
+///     users (id) {
+///         id -> Integer,
+///         name -> Text,
+///     }
+/// }
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut conn = SqliteConnection::establish(":memory:")?;
+/// # diesel::sql_query("CREATE TABLE users (id INTEGER PRIMARY KEY NOT NULL, name TEXT NOT NULL)")
+/// #     .execute(&mut conn)?;
+///
+/// let id = id30::insert_with_random_id(
+///     &mut conn,
+///     users::table,
+///     &mut rand::thread_rng(),
+///     10,
+///     |id| (users::id.eq(id), users::name.eq("Ferris")),
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "rand08")]
+pub fn insert_with_random_id<T, V, Conn, R, F>(
+    conn: &mut Conn,
+    table: T,
+    rng: &mut R,
+    max_attempts: usize,
+    mut values: F,
+) -> QueryResult<Id30>
+where
+    T: Table + Copy,
+    V: Insertable<T>,
+    InsertStatement<T, V::Values>: ExecuteDsl<Conn>,
+    Conn: Connection,
+    R: rand08::Rng + ?Sized,
+    F: FnMut(Id30) -> V,
+{
+    let mut attempts = 0;
+    loop {
+        let id: Id30 = rng.gen();
+        attempts += 1;
+
+        match diesel2::insert_into(table).values(values(id)).execute(conn) {
+            Ok(_) => return Ok(id),
+            Err(Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _))
+                if attempts < max_attempts =>
+            {
+                continue
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -106,4 +328,307 @@ mod test {
 
         Ok(())
     }
+
+    #[cfg(feature = "diesel2_sqlite")]
+    #[test]
+    fn bigint_to_sql() -> Result<(), Box<dyn Error>> {
+        let mut conn = SqliteConnection::establish(":memory:")?;
+
+        #[derive(QueryableByName, PartialEq, Eq, Debug)]
+        struct Row {
+            #[diesel(sql_type = BigInt)]
+            int: i64,
+        }
+
+        let res = sql_query("SELECT ? as int")
+            .bind::<BigInt, _>(Id30::try_from(0x1234_5678).unwrap())
+            .load::<Row>(&mut conn)?;
+
+        assert_eq!(&[Row { int: 0x1234_5678 }], res.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn bigint_from_sql() -> Result<(), Box<dyn Error>> {
+        let mut conn = SqliteConnection::establish(":memory:")?;
+
+        #[derive(QueryableByName, PartialEq, Eq, Debug)]
+        struct Row {
+            #[diesel(sql_type = BigInt)]
+            id30: Id30,
+        }
+
+        let res = sql_query("SELECT 0x12345678 as id30").load::<Row>(&mut conn)?;
+        assert_eq!(res[0].id30, Id30::try_from(0x1234_5678).unwrap());
+
+        for invalid in ["0x40000000", "0x100000000", "-1"] {
+            let res = sql_query(format!("SELECT {invalid} as id30")).load::<Row>(&mut conn);
+            assert!(res.is_err(), "{invalid}");
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "diesel2_mysql")]
+    #[test]
+    fn mysql_unsigned_to_sql() -> Result<(), Box<dyn Error>> {
+        use diesel2::{
+            mysql::Mysql,
+            query_builder::{bind_collector::RawBytesBindCollector, BindCollector},
+        };
+
+        let id30 = Id30::try_from(0x1234_5678).unwrap();
+        let mut collector = RawBytesBindCollector::<Mysql>::new();
+        collector.push_bound_value::<Unsigned<Integer>, _>(&id30, &mut ())?;
+        collector.push_bound_value::<BigInt, _>(&id30, &mut ())?;
+
+        assert_eq!(
+            collector.binds,
+            [
+                Some(0x1234_5678u32.to_ne_bytes().to_vec()),
+                Some(0x1234_5678i64.to_ne_bytes().to_vec()),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "diesel2_sqlite")]
+    #[test]
+    fn text_to_sql_is_canonical() -> Result<(), Box<dyn Error>> {
+        let mut conn = SqliteConnection::establish(":memory:")?;
+
+        #[derive(QueryableByName, PartialEq, Eq, Debug)]
+        struct Row {
+            #[diesel(sql_type = Text)]
+            text: String,
+
+            #[diesel(sql_type = Text)]
+            canonical: String,
+        }
+
+        let id30: Id30 = "BPV3UQ".parse()?;
+        let res = sql_query("SELECT ? as text, ? as canonical")
+            .bind::<Text, _>(id30)
+            .bind::<Text, _>(CanonicalId30(id30))
+            .load::<Row>(&mut conn)?;
+
+        assert_eq!(
+            &[Row {
+                text: "bpv3uq".to_string(),
+                canonical: "bpv3uq".to_string(),
+            }],
+            res.as_slice()
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "diesel2_mysql")]
+    #[test]
+    fn mysql_text_to_sql_is_canonical() -> Result<(), Box<dyn Error>> {
+        use diesel2::{
+            mysql::Mysql,
+            query_builder::{bind_collector::RawBytesBindCollector, BindCollector},
+        };
+
+        let id30: Id30 = "BPV3UQ".parse()?;
+        let mut collector = RawBytesBindCollector::<Mysql>::new();
+        collector.push_bound_value::<Text, _>(&id30, &mut ())?;
+
+        assert_eq!(collector.binds, [Some(b"bpv3uq".to_vec())]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn text_from_sql() -> Result<(), Box<dyn Error>> {
+        let mut conn = SqliteConnection::establish(":memory:")?;
+
+        #[derive(QueryableByName, PartialEq, Eq, Debug)]
+        struct Row {
+            #[diesel(sql_type = Text)]
+            id30: Id30,
+
+            #[diesel(sql_type = Text)]
+            parse: Id30Parse,
+        }
+
+        let expected: Id30 = "bpv3uq".parse()?;
+
+        let res = sql_query("SELECT 'bpv3uq' as id30, 'bpv3uq' as parse").load::<Row>(&mut conn)?;
+        assert_eq!(
+            &[Row {
+                id30: expected,
+                parse: Id30Parse {
+                    id30: expected,
+                    is_canonical: true
+                }
+            }],
+            res.as_slice()
+        );
+
+        let res = sql_query("SELECT 'BPV3UQ' as id30, 'bpv3uq' as parse").load::<Row>(&mut conn)?;
+        assert_eq!(res[0].id30, expected);
+
+        let res = sql_query("SELECT 'bpv3uq' as id30, 'bpv3Uq' as parse").load::<Row>(&mut conn)?;
+        assert_eq!(
+            res[0].parse,
+            Id30Parse {
+                id30: expected,
+                is_canonical: false
+            }
+        );
+
+        let res = sql_query("SELECT 'bpv3u' as id30, 'bpv3uq' as parse").load::<Row>(&mut conn);
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn text_from_sql_canonical_only() -> Result<(), Box<dyn Error>> {
+        let mut conn = SqliteConnection::establish(":memory:")?;
+
+        #[derive(QueryableByName, PartialEq, Eq, Debug)]
+        struct Row {
+            #[diesel(sql_type = Text)]
+            id30: CanonicalId30,
+        }
+
+        let res = sql_query("SELECT 'bpv3uq' as id30").load::<Row>(&mut conn)?;
+        assert_eq!(res[0].id30, CanonicalId30("bpv3uq".parse()?));
+
+        let res = sql_query("SELECT 'BPV3UQ' as id30").load::<Row>(&mut conn);
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "diesel2_sqlite")]
+    #[test]
+    fn text_order_by_matches_ord() -> Result<(), Box<dyn Error>> {
+        #[derive(QueryableByName, PartialEq, Eq, Debug)]
+        struct Row {
+            #[diesel(sql_type = Text)]
+            id: Id30,
+        }
+
+        let mut conn = SqliteConnection::establish(":memory:")?;
+        sql_query("CREATE TABLE items (id TEXT PRIMARY KEY NOT NULL)").execute(&mut conn)?;
+
+        let mut ids: Vec<Id30> = (0..500u32)
+            .map(|i| Id30::try_from(i.wrapping_mul(0x9e37_79b9) >> 2).unwrap())
+            .collect();
+        for &id in &ids {
+            sql_query("INSERT INTO items (id) VALUES (?)")
+                .bind::<Text, _>(id)
+                .execute(&mut conn)?;
+        }
+
+        let ordered: Vec<Id30> = sql_query("SELECT id FROM items ORDER BY id")
+            .load::<Row>(&mut conn)?
+            .into_iter()
+            .map(|row| row.id)
+            .collect();
+
+        ids.sort();
+        assert_eq!(ordered, ids);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "rand08")]
+    mod insert_with_random_id {
+        use rand08::rngs::mock::StepRng;
+
+        use super::*;
+        use crate::insert_with_random_id;
+
+        diesel::table! {
+            users (id) {
+                id -> Integer,
+                name -> Nullable<Text>,
+            }
+        }
+
+        fn setup(existing: &[u32]) -> Result<SqliteConnection, Box<dyn Error>> {
+            let mut conn = SqliteConnection::establish(":memory:")?;
+            sql_query("CREATE TABLE users (id INTEGER PRIMARY KEY NOT NULL, name TEXT NOT NULL)")
+                .execute(&mut conn)?;
+            for &id in existing {
+                diesel::insert_into(users::table)
+                    .values((
+                        users::id.eq(Id30::try_from(id)?),
+                        users::name.eq("existing"),
+                    ))
+                    .execute(&mut conn)?;
+            }
+            Ok(conn)
+        }
+
+        /// Yields the `Id30` values 0, 1, 2, ...
+        fn rng() -> StepRng {
+            StepRng::new(0, 4)
+        }
+
+        #[test]
+        fn retries_on_collision() -> Result<(), Box<dyn Error>> {
+            let mut conn = setup(&[0, 1])?;
+
+            let id = insert_with_random_id(&mut conn, users::table, &mut rng(), 3, |id| {
+                (users::id.eq(id), users::name.eq("new"))
+            })?;
+            assert_eq!(id, Id30::try_from(2u32)?);
+
+            let name: Option<String> =
+                users::table.find(id).select(users::name).first(&mut conn)?;
+            assert_eq!(name.as_deref(), Some("new"));
+
+            Ok(())
+        }
+
+        #[test]
+        fn gives_up_after_max_attempts() -> Result<(), Box<dyn Error>> {
+            let mut conn = setup(&[0, 1])?;
+
+            let mut attempted = vec![];
+            let res = insert_with_random_id(&mut conn, users::table, &mut rng(), 2, |id| {
+                attempted.push(id);
+                (users::id.eq(id), users::name.eq("new"))
+            });
+            assert!(matches!(
+                res,
+                Err(diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _
+                ))
+            ));
+            assert_eq!(attempted, [Id30::try_from(0u32)?, Id30::try_from(1u32)?]);
+
+            Ok(())
+        }
+
+        #[test]
+        fn other_errors_are_not_retried() -> Result<(), Box<dyn Error>> {
+            let mut conn = setup(&[])?;
+
+            let mut attempts = 0;
+            let res = insert_with_random_id(&mut conn, users::table, &mut rng(), 10, |id| {
+                attempts += 1;
+                (users::id.eq(id), users::name.eq(None::<String>))
+            });
+            assert!(matches!(
+                res,
+                Err(diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::NotNullViolation,
+                    _
+                ))
+            ));
+            assert_eq!(attempts, 1);
+
+            Ok(())
+        }
+    }
 }
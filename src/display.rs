@@ -28,7 +28,11 @@ pub(crate) mod generic {
         // SAFETY:
         // - buf is utf8 because the ENCODE table is all ASCII
         // - transmute is safe because all elements have been initialized
-        f(unsafe { std::str::from_utf8_unchecked(std::mem::transmute(buf.as_slice())) })
+        f(unsafe {
+            std::str::from_utf8_unchecked(std::mem::transmute::<&[MaybeUninit<u8>], &[u8]>(
+                buf.as_slice(),
+            ))
+        })
     }
 }
 
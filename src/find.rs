@@ -0,0 +1,305 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::ops::Range;
+
+use crate::{codec_tables::DECODE, Id30, Id30Parse};
+
+/// A regular expression pattern matching canonical Id30 strings at word
+/// boundaries, as found by [`find_iter`] with the default options.
+///
+/// The pattern is compatible with the `regex` crate and most other regular
+/// expression engines.
+pub const REGEX: &str = r"\b[0-9a-hjkmnp-rt-z]{6}\b";
+
+/// Options for finding Id30 strings in free text.
+///
+/// The default options find canonical Id30 strings only, which is a good
+/// choice for text where the IDs are copied and pasted, such as commit
+/// messages and chat logs. For text that is typed by people, such as support
+/// tickets, allowing confusables also finds IDs written in upper case or with
+/// confusable characters. This also finds many ordinary words, for example
+/// "people" or "Search", and [`Finder::min_entropy`] can be used to skip some
+/// of them.
+///
+/// ```
+/// # use id30::Finder;
+/// let finder = Finder::new().confusables(true).min_entropy(2.0);
+/// let found: Vec<String> = finder
+///     .find_iter("Our people say BPV3UQ is down")
+///     .map(|m| m.id30.to_string())
+///     .collect();
+/// assert_eq!(found, ["bpv3uq"]);
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Finder {
+    confusables: bool,
+    min_entropy: f64,
+}
+
+/// An Id30 string found in free text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Id30Match<'h> {
+    /// The byte range of the match in the searched text
+    pub span: Range<usize>,
+
+    /// The matched text
+    pub text: &'h str,
+
+    /// The parsed value
+    pub id30: Id30,
+
+    /// `true` iff the matched text is in the canonical encoding
+    pub is_canonical: bool,
+}
+
+/// An iterator over the Id30 strings in a text, see [`Finder::find_iter`]
+#[derive(Debug, Clone)]
+pub struct Id30Matches<'h> {
+    finder: Finder,
+    haystack: &'h str,
+    pos: usize,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// The Shannon entropy, in bits per character, of the distribution of symbols
+/// in `token`. This is at most `log2(6) ≈ 2.58` for six distinct symbols.
+fn entropy(token: &[u8]) -> f64 {
+    let mut counts = [0u8; 32];
+    for &c in token {
+        counts[(DECODE[c as usize] & 0b1_1111) as usize] += 1;
+    }
+
+    let len = token.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+impl Finder {
+    /// Options that match canonical Id30 strings only, without an entropy
+    /// threshold
+    pub const fn new() -> Finder {
+        Finder {
+            confusables: false,
+            min_entropy: 0.,
+        }
+    }
+
+    /// Whether to also match Id30 strings that are not canonical, ie that
+    /// contain upper case or confusable characters
+    pub const fn confusables(mut self, confusables: bool) -> Finder {
+        self.confusables = confusables;
+        self
+    }
+
+    /// Skip matches that have less than `bits` bits of entropy per
+    /// character, measured as the Shannon entropy of the distribution of the
+    /// decoded symbols in the match.
+    ///
+    /// This is a heuristic for telling ordinary words apart from random IDs,
+    /// which rarely repeat symbols. For example, "people" has an entropy of
+    /// 1.92, while six distinct symbols, as in "bpv3uq", have an entropy of
+    /// 2.58. A threshold of 2.0 skips about 6% of uniformly random IDs.
+    pub const fn min_entropy(mut self, bits: f64) -> Finder {
+        self.min_entropy = bits;
+        self
+    }
+
+    /// Iterate over the Id30 strings in `haystack`.
+    ///
+    /// A match is a sequence of six characters at word boundaries, ie that is
+    /// neither preceded nor followed by an alphanumeric character or `_`.
+    pub fn find_iter<'h>(&self, haystack: &'h str) -> Id30Matches<'h> {
+        Id30Matches {
+            finder: *self,
+            haystack,
+            pos: 0,
+        }
+    }
+
+    /// Replace each Id30 string in `haystack` with the result of `replacement`
+    /// for the match.
+    ///
+    /// ```
+    /// # use id30::Finder;
+    /// let linked = Finder::new().replace_all("order bpv3uq failed", |m| {
+    ///     format!("<a href=\"/orders/{}\">{}</a>", m.id30, m.text)
+    /// });
+    /// assert_eq!(linked, "order <a href=\"/orders/bpv3uq\">bpv3uq</a> failed");
+    /// ```
+    pub fn replace_all(
+        &self,
+        haystack: &str,
+        mut replacement: impl FnMut(&Id30Match) -> String,
+    ) -> String {
+        let mut result = String::with_capacity(haystack.len());
+        let mut last = 0;
+        for m in self.find_iter(haystack) {
+            result.push_str(&haystack[last..m.span.start]);
+            result.push_str(&replacement(&m));
+            last = m.span.end;
+        }
+        result.push_str(&haystack[last..]);
+        result
+    }
+
+    fn accept(&self, token: &str) -> Option<Id30Parse> {
+        let parse: Id30Parse = token.parse().ok()?;
+        if !self.confusables && !parse.is_canonical {
+            return None;
+        }
+        if self.min_entropy > 0. && entropy(token.as_bytes()) < self.min_entropy {
+            return None;
+        }
+        Some(parse)
+    }
+}
+
+impl<'h> Iterator for Id30Matches<'h> {
+    type Item = Id30Match<'h>;
+
+    fn next(&mut self) -> Option<Id30Match<'h>> {
+        while self.pos < self.haystack.len() {
+            let rest = &self.haystack[self.pos..];
+
+            let Some(start) = rest.find(is_word_char) else {
+                self.pos = self.haystack.len();
+                break;
+            };
+            let word = &rest[start..];
+            let len = word.find(|c| !is_word_char(c)).unwrap_or(word.len());
+            let word = &word[..len];
+
+            let start = self.pos + start;
+            self.pos = start + len;
+
+            if let Some(parse) = self.finder.accept(word) {
+                return Some(Id30Match {
+                    span: start..start + len,
+                    text: word,
+                    id30: parse.id30,
+                    is_canonical: parse.is_canonical,
+                });
+            }
+        }
+        None
+    }
+}
+
+/// Iterate over the canonical Id30 strings in `haystack`, at word boundaries.
+///
+/// See [`Finder`] for more options.
+///
+/// ```
+/// let found: Vec<_> = id30::find_iter("order bpv3uq failed, see zvaec2")
+///     .map(|m| (m.span, m.id30.to_string()))
+///     .collect();
+/// assert_eq!(found, [(6..12, "bpv3uq".to_string()), (25..31, "zvaec2".to_string())]);
+/// ```
+pub fn find_iter(haystack: &str) -> Id30Matches<'_> {
+    Finder::new().find_iter(haystack)
+}
+
+/// Replace each canonical Id30 string in `haystack` with the result of
+/// `replacement` for the match, for example for auto-linking.
+///
+/// See [`Finder::replace_all`].
+pub fn replace_all(haystack: &str, replacement: impl FnMut(&Id30Match) -> String) -> String {
+    Finder::new().replace_all(haystack, replacement)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn found(finder: Finder, haystack: &str) -> Vec<&str> {
+        finder.find_iter(haystack).map(|m| m.text).collect()
+    }
+
+    #[test]
+    fn word_boundaries() {
+        let finder = Finder::new();
+        assert_eq!(
+            found(finder, "bpv3uq,zvaec2 (rfmbyz) x:jwygvk."),
+            ["bpv3uq", "zvaec2", "rfmbyz", "jwygvk"]
+        );
+        assert_eq!(
+            found(
+                finder,
+                "bpv3uqq xbpv3uq bpv3u _bpv3uq bpv3uq_ æbpv3uq bpv3uqø"
+            ),
+            Vec::<&str>::new()
+        );
+    }
+
+    #[test]
+    fn spans() {
+        let haystack = "ø bpv3uq";
+        let m = find_iter(haystack).next().unwrap();
+        assert_eq!(m.span, 3..9);
+        assert_eq!(&haystack[m.span], "bpv3uq");
+        assert_eq!(m.id30, "bpv3uq".parse().unwrap());
+        assert!(m.is_canonical);
+    }
+
+    #[test]
+    fn strict_and_confusables() {
+        let haystack = "order BPV3UQ or bpv3uq or bov3uq or people";
+        assert_eq!(found(Finder::new(), haystack), ["bpv3uq"]);
+
+        let finder = Finder::new().confusables(true);
+        assert_eq!(
+            found(finder, haystack),
+            ["BPV3UQ", "bpv3uq", "bov3uq", "people"]
+        );
+        assert!(!finder.find_iter(haystack).next().unwrap().is_canonical);
+    }
+
+    #[test]
+    fn min_entropy() {
+        let finder = Finder::new().confusables(true).min_entropy(2.0);
+        assert_eq!(
+            found(finder, "people bpv3uq zzzzzz bov3uq 000ooo"),
+            ["bpv3uq", "bov3uq"]
+        );
+        assert!((entropy(b"people") - 1.918).abs() < 0.001);
+        assert!((entropy(b"bpv3uq") - 6f64.log2()).abs() < 0.001);
+        assert_eq!(entropy(b"0oO0oO"), 0.);
+    }
+
+    #[test]
+    fn replace() {
+        assert_eq!(
+            replace_all("bpv3uq and zvaec2!", |m| format!("[{}]", m.id30)),
+            "[bpv3uq] and [zvaec2]!"
+        );
+        assert_eq!(
+            replace_all("nothing here", |_| unreachable!()),
+            "nothing here"
+        );
+    }
+
+    #[test]
+    fn regex() {
+        let regex = regex1::Regex::new(REGEX).unwrap();
+        let haystack = "order bpv3uq failed, BPV3UQ, bpv3uqq, zvaec2 bov3uq ø rfmbyz";
+        let expected: Vec<&str> = find_iter(haystack).map(|m| m.text).collect();
+        let found: Vec<&str> = regex.find_iter(haystack).map(|m| m.as_str()).collect();
+        assert_eq!(found, expected);
+        assert_eq!(found, ["bpv3uq", "zvaec2", "rfmbyz"]);
+    }
+}
@@ -47,6 +47,26 @@ impl TryFrom<i32> for Id30 {
     }
 }
 
+impl TryFrom<u64> for Id30 {
+    type Error = OutOfRangeError;
+
+    fn try_from(value: u64) -> Result<Self, Self::Error> {
+        u32::try_from(value)
+            .map_err(|_| OutOfRangeError)
+            .and_then(Id30::try_from)
+    }
+}
+
+impl TryFrom<i64> for Id30 {
+    type Error = OutOfRangeError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        u32::try_from(value)
+            .map_err(|_| OutOfRangeError)
+            .and_then(Id30::try_from)
+    }
+}
+
 impl From<Id30> for u32 {
     fn from(value: Id30) -> Self {
         value.0
@@ -59,6 +79,18 @@ impl From<Id30> for i32 {
     }
 }
 
+impl From<Id30> for u64 {
+    fn from(value: Id30) -> Self {
+        value.0.into()
+    }
+}
+
+impl From<Id30> for i64 {
+    fn from(value: Id30) -> Self {
+        value.0.into()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -71,9 +103,15 @@ mod test {
         assert!(Id30::try_from(0x1234_5678u32).is_ok());
 
         assert!(Id30::try_from(0i32).is_ok());
-        assert!(Id30::try_from(((1 << 30) - 1) as i32).is_ok());
+        assert!(Id30::try_from((1i32 << 30) - 1).is_ok());
         assert!(Id30::try_from(0x3fff_ffffi32).is_ok());
         assert!(Id30::try_from(0x1234_5678i32).is_ok());
+
+        assert!(Id30::try_from(0u64).is_ok());
+        assert!(Id30::try_from(0x3fff_ffffu64).is_ok());
+
+        assert!(Id30::try_from(0i64).is_ok());
+        assert!(Id30::try_from(0x3fff_ffffi64).is_ok());
     }
 
     #[test]
@@ -83,5 +121,19 @@ mod test {
 
         assert!(Id30::try_from(-0x8000_0000).is_err());
         assert!(Id30::try_from(-1).is_err());
+
+        assert!(Id30::try_from(0x4000_0000u64).is_err());
+        assert!(Id30::try_from(0x1_0000_0000u64).is_err());
+
+        assert!(Id30::try_from(0x4000_0000i64).is_err());
+        assert!(Id30::try_from(0x1_0000_0000i64).is_err());
+        assert!(Id30::try_from(-1i64).is_err());
+    }
+
+    #[test]
+    fn into_wide() {
+        let id30 = Id30::try_from(0x1234_5678u32).unwrap();
+        assert_eq!(u64::from(id30), 0x1234_5678);
+        assert_eq!(i64::from(id30), 0x1234_5678);
     }
 }
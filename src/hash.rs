@@ -0,0 +1,177 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasher, Hasher},
+};
+
+use crate::Id30;
+
+/// Multiplier for Fibonacci hashing, `2⁶⁴ / φ` rounded to an odd number
+const MIX: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A [`BuildHasher`] for hash tables keyed by [`Id30`].
+///
+/// The default hasher of `HashMap` runs SipHash over its keys, which is
+/// unnecessary work for `Id30` values that are already uniformly random, as
+/// when they are generated via `rand`. The hashers built by `Id30BuildHasher`
+/// pass the value through instead.
+///
+/// For IDs that are not random, for example sequentially assigned ones, use
+/// [`Id30BuildHasher::with_mixing`], which additionally spreads the value over
+/// all the bits of the hash with a single multiplication.
+///
+/// Neither variant offers any protection against HashDoS, so they should not
+/// be used for keys that are chosen by an adversary.
+///
+/// ```
+/// # use id30::{Id30, Id30Map};
+/// let mut map = Id30Map::default();
+/// let id: Id30 = "bpv3uq".parse().unwrap();
+/// map.insert(id, "value");
+/// assert_eq!(map.get(&id), Some(&"value"));
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Id30BuildHasher {
+    mix: bool,
+}
+
+impl Id30BuildHasher {
+    /// A `BuildHasher` that passes the `Id30` value through
+    pub const fn new() -> Self {
+        Id30BuildHasher { mix: false }
+    }
+
+    /// A `BuildHasher` that mixes the bits of the `Id30` value, for IDs that
+    /// are not uniformly random
+    pub const fn with_mixing() -> Self {
+        Id30BuildHasher { mix: true }
+    }
+}
+
+impl BuildHasher for Id30BuildHasher {
+    type Hasher = Id30Hasher;
+
+    fn build_hasher(&self) -> Id30Hasher {
+        Id30Hasher {
+            hash: 0,
+            mix: self.mix,
+        }
+    }
+}
+
+/// The [`Hasher`] built by [`Id30BuildHasher`].
+///
+/// `Id30Hasher` is intended for hashing `Id30` values only. Other input is
+/// accepted, but hashed with a trivial and weak hash function.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Id30Hasher {
+    hash: u64,
+    mix: bool,
+}
+
+impl Hasher for Id30Hasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.hash = (self.hash.rotate_left(8) ^ byte as u64).wrapping_mul(MIX);
+        }
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        let i = i as u64;
+        let v = if self.mix {
+            i.wrapping_mul(MIX)
+        } else {
+            // Hash tables like the standard HashMap pick buckets with the low
+            // bits and use the top seven bits as a tag, so the value is
+            // duplicated into the high bits as well:
+            i | (i << 34)
+        };
+        // The state starts at zero, so a single Id30 passes through unchanged
+        self.hash = self.hash.wrapping_mul(MIX) ^ v;
+    }
+}
+
+/// A `HashMap` keyed by [`Id30`] using [`Id30BuildHasher`]
+pub type Id30Map<V> = HashMap<Id30, V, Id30BuildHasher>;
+
+/// A `HashSet` of [`Id30`] using [`Id30BuildHasher`].
+///
+/// See also [`Id30Set`][crate::Id30Set] for a more compact representation of
+/// large sets.
+pub type Id30HashSet = HashSet<Id30, Id30BuildHasher>;
+
+#[cfg(feature = "nohash_hasher02")]
+mod nohash_hasher02_impl {
+    use crate::Id30;
+
+    impl nohash_hasher02::IsEnabled for Id30 {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pass_through() {
+        let id30 = Id30::try_from(0x1234_5678).unwrap();
+        let hash = Id30BuildHasher::new().hash_one(id30);
+        assert_eq!(hash as u32, 0x1234_5678);
+        assert_eq!(hash >> 57, 0x1234_5678 >> 23);
+    }
+
+    #[test]
+    fn mixing_spreads_sequential_ids() {
+        let tags: HashSet<u64> = (0..128)
+            .map(|i| Id30BuildHasher::with_mixing().hash_one(Id30::try_from(i).unwrap()) >> 57)
+            .collect();
+        assert!(tags.len() > 64);
+    }
+
+    #[test]
+    fn composite_keys() {
+        let [a, b, x] = [1, 2, 0x1234_5678].map(|i| Id30::try_from(i).unwrap());
+        for hasher in [Id30BuildHasher::new(), Id30BuildHasher::with_mixing()] {
+            assert_ne!(hasher.hash_one((a, x)), hasher.hash_one((b, x)));
+            assert_ne!(hasher.hash_one(("a", x)), hasher.hash_one(("b", x)));
+        }
+    }
+
+    #[test]
+    fn map_and_set() {
+        let ids: Vec<Id30> = (0..1000)
+            .map(|i| Id30::try_from(i * 7919).unwrap())
+            .collect();
+
+        let map: Id30Map<usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+        let mut set = Id30HashSet::with_hasher(Id30BuildHasher::with_mixing());
+        set.extend(ids.iter().copied());
+
+        for (i, id) in ids.iter().enumerate() {
+            assert_eq!(map.get(id), Some(&i));
+            assert!(set.contains(id));
+        }
+        assert!(!set.contains(&Id30::try_from(1).unwrap()));
+    }
+
+    #[cfg(feature = "nohash_hasher02")]
+    #[test]
+    fn nohash_hasher() {
+        let mut map: HashMap<Id30, &str, nohash_hasher02::BuildNoHashHasher<Id30>> =
+            Default::default();
+        let id30 = Id30::try_from(0x1234_5678).unwrap();
+        map.insert(id30, "value");
+        assert_eq!(map.get(&id30), Some(&"value"));
+    }
+}
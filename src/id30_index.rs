@@ -0,0 +1,285 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    codec_tables::{DECODE, ERR_FLAG, SUBSTITUTION_COST},
+    Id30, Id30Set,
+};
+
+/// Cost of two adjacent characters having been swapped
+const TRANSPOSITION_COST: u32 = 1;
+
+/// Cost of a character missing from, or being extraneous in, the input
+const INSERTION_DELETION_COST: u32 = 2;
+
+/// Cost of replacing a character that is not valid in Id30 at all
+const INVALID_SUBSTITUTION_COST: u32 = 3;
+
+/// A set of known [`Id30`] values that supports typo-tolerant lookup.
+///
+/// When a user enters an ID that does not exist, [`Id30Index::suggest`] finds
+/// the known IDs that the user might have meant. It considers the known IDs
+/// that are within one edit of the input, where an edit is one of:
+///  - substituting one character
+///  - swapping two adjacent characters
+///  - inserting or deleting one character, so inputs of length 5 and 7, which
+///    are not valid Id30 strings, can also be resolved
+///
+/// The suggestions are ranked by a cost model that considers substitutions
+/// between neighbouring keys on a QWERTY keyboard and between visually similar
+/// characters to be more likely than other substitutions. Decoding of the input
+/// is otherwise as for parsing, so it is case-insensitive and handles the
+/// confusable characters of the Id30 encoding without cost.
+///
+/// ```
+/// # use id30::{Id30, Id30Index};
+/// let known: Vec<Id30> = ["bpv3uq", "zvaec2", "rfmbyz"]
+///     .iter()
+///     .map(|x| x.parse().unwrap())
+///     .collect();
+/// let index: Id30Index = known.into_iter().collect();
+///
+/// let suggestions = index.suggest("bpv3yq");
+/// assert_eq!(&suggestions[0].id30.to_string(), "bpv3uq");
+///
+/// let suggestions = index.suggest("zvaec");
+/// assert_eq!(&suggestions[0].id30.to_string(), "zvaec2");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Id30Index {
+    ids: Id30Set,
+}
+
+/// A suggestion from [`Id30Index::suggest`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// The suggested ID
+    pub id30: Id30,
+
+    /// The estimated cost of the edit that turns the suggested ID into the
+    /// given input. Lower is more likely. An exact match has cost 0.
+    pub cost: u32,
+}
+
+fn compose(symbols: &[u8; 6]) -> Id30 {
+    Id30(
+        symbols
+            .iter()
+            .zip([25, 20, 15, 10, 5, 0])
+            .map(|(&s, shift)| (s as u32) << shift)
+            .fold(0, |a, b| a | b),
+    )
+}
+
+impl Id30Index {
+    /// Create an empty index
+    pub fn new() -> Id30Index {
+        Id30Index::default()
+    }
+
+    /// Add `id` to the index. Returns `true` iff `id` was not already present.
+    pub fn insert(&mut self, id: Id30) -> bool {
+        self.ids.insert(id)
+    }
+
+    /// `true` iff the index contains `id`
+    pub fn contains(&self, id: Id30) -> bool {
+        self.ids.contains(id)
+    }
+
+    /// The number of IDs in the index
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// `true` iff the index contains no IDs
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Find the known IDs within one edit of `input`, ordered from most to
+    /// least likely, ie by cost and then by ID.
+    ///
+    /// If `input` parses as an `Id30` that is in the index, it is the first
+    /// suggestion, with cost 0.
+    pub fn suggest(&self, input: &str) -> Vec<Suggestion> {
+        let symbols: Vec<Option<u8>> = input
+            .chars()
+            .map(|c| {
+                let decoded = DECODE[c as usize & 0xff];
+                (c.is_ascii() && decoded & ERR_FLAG == 0).then_some(decoded & 0b1_1111)
+            })
+            .collect();
+
+        let mut candidates = BTreeMap::new();
+        let mut candidate = |symbols: [u8; 6], cost: u32| {
+            let id30 = compose(&symbols);
+            let best = candidates.entry(id30).or_insert(cost);
+            *best = cost.min(*best);
+        };
+
+        let invalid: Vec<usize> = (0..symbols.len())
+            .filter(|&i| symbols[i].is_none())
+            .collect();
+
+        match (symbols.len(), invalid.as_slice()) {
+            (6, []) => {
+                let typed: [u8; 6] = std::array::from_fn(|i| symbols[i].unwrap());
+                candidate(typed, 0);
+
+                for i in 0..6 {
+                    for intended in 0..32 {
+                        let mut s = typed;
+                        s[i] = intended;
+                        let cost = SUBSTITUTION_COST[intended as usize][typed[i] as usize];
+                        candidate(s, cost as u32);
+                    }
+                }
+
+                for i in 0..5 {
+                    let mut s = typed;
+                    s.swap(i, i + 1);
+                    if s != typed {
+                        candidate(s, TRANSPOSITION_COST);
+                    }
+                }
+            }
+            (6, [_]) => {
+                for intended in 0..32 {
+                    let s = std::array::from_fn(|j| symbols[j].unwrap_or(intended));
+                    candidate(s, INVALID_SUBSTITUTION_COST);
+                }
+            }
+            (5, []) => {
+                for i in 0..6 {
+                    for intended in 0..32 {
+                        let s = std::array::from_fn(|j| match j.cmp(&i) {
+                            std::cmp::Ordering::Less => symbols[j].unwrap(),
+                            std::cmp::Ordering::Equal => intended,
+                            std::cmp::Ordering::Greater => symbols[j - 1].unwrap(),
+                        });
+                        candidate(s, INSERTION_DELETION_COST);
+                    }
+                }
+            }
+            (7, invalid) if invalid.len() <= 1 => {
+                for i in 0..7 {
+                    if invalid.iter().any(|&j| j != i) {
+                        continue;
+                    }
+                    let s =
+                        std::array::from_fn(|j| symbols[if j < i { j } else { j + 1 }].unwrap());
+                    candidate(s, INSERTION_DELETION_COST);
+                }
+            }
+            _ => {}
+        }
+
+        let mut suggestions: Vec<Suggestion> = candidates
+            .into_iter()
+            .filter(|&(id30, _)| self.ids.contains(id30))
+            .map(|(id30, cost)| Suggestion { id30, cost })
+            .collect();
+        suggestions.sort_by_key(|suggestion| suggestion.cost);
+        suggestions
+    }
+}
+
+impl From<Id30Set> for Id30Index {
+    fn from(ids: Id30Set) -> Id30Index {
+        Id30Index { ids }
+    }
+}
+
+impl FromIterator<Id30> for Id30Index {
+    fn from_iter<T: IntoIterator<Item = Id30>>(iter: T) -> Id30Index {
+        Id30Index {
+            ids: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Id30> for Id30Index {
+    fn extend<T: IntoIterator<Item = Id30>>(&mut self, iter: T) {
+        self.ids.extend(iter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn index(ids: &[&str]) -> Id30Index {
+        ids.iter().map(|x| x.parse::<Id30>().unwrap()).collect()
+    }
+
+    fn suggest(index: &Id30Index, input: &str) -> Vec<(String, u32)> {
+        index
+            .suggest(input)
+            .into_iter()
+            .map(|s| (s.id30.to_string(), s.cost))
+            .collect()
+    }
+
+    fn s(id30: &str, cost: u32) -> (String, u32) {
+        (id30.to_string(), cost)
+    }
+
+    #[test]
+    fn exact_match() {
+        let index = index(&["bpv3uq", "bpv3vq"]);
+        assert_eq!(suggest(&index, "BPV3UQ"), [s("bpv3uq", 0), s("bpv3vq", 1)]);
+    }
+
+    #[test]
+    fn substitution_ranking() {
+        // `y` is a neighbour key of `u`, `p` is not a neighbour of `q`
+        let index = index(&["bpv3yq", "bpv3up"]);
+        assert_eq!(suggest(&index, "bpv3uq"), [s("bpv3yq", 1), s("bpv3up", 3)]);
+    }
+
+    #[test]
+    fn confusables_are_near() {
+        // `k` is a neighbour of `l`, which is an encoding of `1`
+        let index = index(&["bpv31q"]);
+        assert_eq!(suggest(&index, "bpv3kq"), [s("bpv31q", 1)]);
+    }
+
+    #[test]
+    fn transposition() {
+        let index = index(&["bpv3uq"]);
+        assert_eq!(suggest(&index, "bp3vuq"), [s("bpv3uq", 1)]);
+    }
+
+    #[test]
+    fn insertion_and_deletion() {
+        let index = index(&["bpv3uq", "zzzzzz"]);
+        assert_eq!(suggest(&index, "bpvuq"), [s("bpv3uq", 2)]);
+        assert_eq!(suggest(&index, "bpv3uqq"), [s("bpv3uq", 2)]);
+        assert_eq!(suggest(&index, "bpv3!uq"), [s("bpv3uq", 2)]);
+        assert_eq!(suggest(&index, "zzzzz"), [s("zzzzzz", 2)]);
+    }
+
+    #[test]
+    fn invalid_characters() {
+        let index = index(&["bpv3uq"]);
+        assert_eq!(suggest(&index, "bpv!uq"), [s("bpv3uq", 3)]);
+        assert_eq!(suggest(&index, "bpvøuq"), [s("bpv3uq", 3)]);
+        assert_eq!(suggest(&index, "bp!!uq"), []);
+    }
+
+    #[test]
+    fn no_suggestions() {
+        let index = index(&["bpv3uq"]);
+        assert_eq!(suggest(&index, "zvaec2"), []);
+        assert_eq!(suggest(&index, "bpv"), []);
+        assert_eq!(suggest(&index, "bpv3uqbpv3uq"), []);
+    }
+}
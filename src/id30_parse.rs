@@ -35,6 +35,7 @@ use crate::Id30;
 /// # Ok(())}
 /// ```
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "diesel2", derive(diesel::FromSqlRow))]
 pub struct Id30Parse {
     #[allow(missing_docs)]
     pub id30: Id30,
@@ -0,0 +1,1149 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    io::{self, Read, Write},
+    ops::{BitAnd, BitOr, Sub},
+};
+
+use crate::Id30;
+
+/// Containers with at most this many elements are stored as sorted arrays,
+/// larger ones as bitmaps. At this size both representations take 8 KiB.
+const ARRAY_MAX: usize = 4096;
+
+const BITMAP_WORDS: usize = 1 << 16 >> 6;
+
+/// The largest chunk key, holding the values up to `(1 << 30) - 1`
+const MAX_KEY: u16 = (((1u32 << 30) - 1) >> 16) as u16;
+
+const SERIAL_COOKIE_NO_RUNS: u32 = 12346;
+const SERIAL_COOKIE: u16 = 12347;
+const NO_OFFSET_THRESHOLD: usize = 4;
+
+/// A compact set of [`Id30`] values.
+///
+/// `Id30Set` is a compressed bitmap in the style of
+/// [Roaring](https://roaringbitmap.org/). The 30 bit value space is divided
+/// into chunks of 2¹⁶ values sharing the same high bits, and each non-empty
+/// chunk is stored in whichever of the following representations is most
+/// compact:
+///  - a sorted array of the low 16 bits, for sparse chunks
+///  - a bitmap of 2¹⁶ bits, for dense chunks
+///  - a sorted list of runs of consecutive values, for clustered chunks
+///
+/// Array and bitmap representations are maintained automatically as values
+/// are inserted and removed. Run representations are only introduced by
+/// [`Id30Set::optimize`].
+///
+/// Iteration yields the values in ascending order, which is the same as the
+/// ordering of [`Id30`] as implemented by `Ord`.
+///
+/// ```
+/// # use id30::{Id30, Id30Set};
+/// let mut set = Id30Set::new();
+/// let id: Id30 = "bpv3uq".parse().unwrap();
+///
+/// assert!(set.insert(id));
+/// assert!(set.contains(id));
+/// assert_eq!(set.len(), 1);
+///
+/// assert!(set.remove(id));
+/// assert!(set.is_empty());
+/// ```
+///
+/// The binary serialization, via [`Id30Set::serialize_into`] and
+/// [`Id30Set::deserialize_from`], follows the [portable Roaring
+/// format](https://github.com/RoaringBitmap/RoaringFormatSpec), so serialized
+/// sets can be read by other Roaring implementations as sets of 32 bit
+/// integers.
+#[derive(Clone, Default)]
+pub struct Id30Set {
+    keys: Vec<u16>,
+    containers: Vec<Container>,
+}
+
+#[derive(Clone)]
+enum Container {
+    Array(Vec<u16>),
+    Bitmap(Box<Bitmap>),
+    Run(Vec<Run>),
+}
+
+#[derive(Clone)]
+struct Bitmap {
+    words: [u64; BITMAP_WORDS],
+    len: u32,
+}
+
+/// An inclusive range of values `start..=last`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Run {
+    start: u16,
+    last: u16,
+}
+
+impl Run {
+    fn len(&self) -> usize {
+        (self.last - self.start) as usize + 1
+    }
+}
+
+fn split(id: Id30) -> (u16, u16) {
+    ((id.0 >> 16) as u16, id.0 as u16)
+}
+
+fn join(key: u16, low: u16) -> Id30 {
+    Id30(((key as u32) << 16) | low as u32)
+}
+
+impl Bitmap {
+    fn from_words(words: [u64; BITMAP_WORDS]) -> Box<Bitmap> {
+        let len = words.iter().map(|w| w.count_ones()).sum();
+        Box::new(Bitmap { words, len })
+    }
+
+    fn contains(&self, v: u16) -> bool {
+        self.words[(v >> 6) as usize] & (1 << (v & 63)) != 0
+    }
+
+    fn insert(&mut self, v: u16) -> bool {
+        let word = &mut self.words[(v >> 6) as usize];
+        let bit = 1 << (v & 63);
+        let inserted = *word & bit == 0;
+        *word |= bit;
+        self.len += inserted as u32;
+        inserted
+    }
+
+    fn remove(&mut self, v: u16) -> bool {
+        let word = &mut self.words[(v >> 6) as usize];
+        let bit = 1 << (v & 63);
+        let removed = *word & bit != 0;
+        *word &= !bit;
+        self.len -= removed as u32;
+        removed
+    }
+
+    fn rank(&self, v: u16) -> usize {
+        let index = (v >> 6) as usize;
+        let below: u32 = self.words[..index].iter().map(|w| w.count_ones()).sum();
+        let partial = self.words[index] & ((1 << (v & 63)) - 1);
+        (below + partial.count_ones()) as usize
+    }
+
+    fn select(&self, mut n: usize) -> Option<u16> {
+        for (index, &word) in self.words.iter().enumerate() {
+            let ones = word.count_ones() as usize;
+            if n < ones {
+                let mut word = word;
+                for _ in 0..n {
+                    word &= word - 1;
+                }
+                return Some((index * 64) as u16 + word.trailing_zeros() as u16);
+            }
+            n -= ones;
+        }
+        None
+    }
+
+    fn run_count(&self) -> usize {
+        let mut runs = 0;
+        let mut carry = 0;
+        for &word in &self.words {
+            // Count the run starts, ie set bits whose lower neighbour is unset
+            let shifted = (word << 1) | carry;
+            runs += (word & !shifted).count_ones() as usize;
+            carry = word >> 63;
+        }
+        runs
+    }
+}
+
+impl Container {
+    fn len(&self) -> usize {
+        match self {
+            Container::Array(values) => values.len(),
+            Container::Bitmap(bitmap) => bitmap.len as usize,
+            Container::Run(runs) => runs.iter().map(Run::len).sum(),
+        }
+    }
+
+    fn contains(&self, v: u16) -> bool {
+        match self {
+            Container::Array(values) => values.binary_search(&v).is_ok(),
+            Container::Bitmap(bitmap) => bitmap.contains(v),
+            Container::Run(runs) => {
+                let index = runs.partition_point(|run| run.start <= v);
+                index > 0 && v <= runs[index - 1].last
+            }
+        }
+    }
+
+    fn insert(&mut self, v: u16) -> bool {
+        let inserted = match self {
+            Container::Array(values) => match values.binary_search(&v) {
+                Ok(_) => false,
+                Err(index) => {
+                    values.insert(index, v);
+                    true
+                }
+            },
+            Container::Bitmap(bitmap) => bitmap.insert(v),
+            Container::Run(runs) => insert_into_runs(runs, v),
+        };
+        self.normalize();
+        inserted
+    }
+
+    fn remove(&mut self, v: u16) -> bool {
+        let removed = match self {
+            Container::Array(values) => match values.binary_search(&v) {
+                Ok(index) => {
+                    values.remove(index);
+                    true
+                }
+                Err(_) => false,
+            },
+            Container::Bitmap(bitmap) => bitmap.remove(v),
+            Container::Run(runs) => remove_from_runs(runs, v),
+        };
+        self.normalize();
+        removed
+    }
+
+    /// The number of values in the container that are less than `v`
+    fn rank(&self, v: u16) -> usize {
+        match self {
+            Container::Array(values) => values.partition_point(|&x| x < v),
+            Container::Bitmap(bitmap) => bitmap.rank(v),
+            Container::Run(runs) => runs
+                .iter()
+                .take_while(|run| run.start < v)
+                .map(|run| (run.last.min(v - 1) - run.start) as usize + 1)
+                .sum(),
+        }
+    }
+
+    fn select(&self, mut n: usize) -> Option<u16> {
+        match self {
+            Container::Array(values) => values.get(n).copied(),
+            Container::Bitmap(bitmap) => bitmap.select(n),
+            Container::Run(runs) => {
+                for run in runs {
+                    if n < run.len() {
+                        return Some(run.start + n as u16);
+                    }
+                    n -= run.len();
+                }
+                None
+            }
+        }
+    }
+
+    fn iter(&self) -> ContainerIter<'_> {
+        match self {
+            Container::Array(values) => ContainerIter::Array(values.iter()),
+            Container::Bitmap(bitmap) => ContainerIter::Bitmap {
+                words: &bitmap.words,
+                index: 0,
+                word: bitmap.words[0],
+            },
+            Container::Run(runs) => ContainerIter::Run {
+                runs: runs.iter(),
+                current: None,
+            },
+        }
+    }
+
+    fn words(&self) -> [u64; BITMAP_WORDS] {
+        match self {
+            Container::Bitmap(bitmap) => bitmap.words,
+            _ => {
+                let mut words = [0; BITMAP_WORDS];
+                for v in self.iter() {
+                    words[(v >> 6) as usize] |= 1 << (v & 63);
+                }
+                words
+            }
+        }
+    }
+
+    fn from_words(words: [u64; BITMAP_WORDS]) -> Container {
+        let mut container = Container::Bitmap(Bitmap::from_words(words));
+        container.normalize();
+        container
+    }
+
+    fn run_count(&self) -> usize {
+        match self {
+            Container::Array(values) => {
+                1 + values
+                    .windows(2)
+                    .filter(|pair| pair[0] + 1 != pair[1])
+                    .count()
+            }
+            Container::Bitmap(bitmap) => bitmap.run_count(),
+            Container::Run(runs) => runs.len(),
+        }
+    }
+
+    /// Switch between array and bitmap representations when the number of
+    /// values crosses `ARRAY_MAX`, and away from the run representation when
+    /// it has become the least compact one.
+    fn normalize(&mut self) {
+        match self {
+            Container::Array(values) if values.len() > ARRAY_MAX => {
+                *self = Container::Bitmap(Bitmap::from_words(self.words()));
+            }
+            Container::Bitmap(bitmap) if bitmap.len as usize <= ARRAY_MAX => {
+                *self = Container::Array(self.iter().collect());
+            }
+            Container::Run(runs) if run_size(runs.len()) > array_size(ARRAY_MAX) => {
+                *self = Container::from_words(self.words());
+            }
+            _ => {}
+        }
+    }
+
+    /// Convert to the most compact of the three representations
+    fn optimize(&mut self) {
+        let len = self.len();
+        let without_runs = array_size(len.min(ARRAY_MAX));
+
+        if run_size(self.run_count()) < without_runs {
+            if !matches!(self, Container::Run(_)) {
+                *self = Container::Run(self.runs());
+            }
+        } else if let Container::Run(_) = self {
+            *self = if len <= ARRAY_MAX {
+                Container::Array(self.iter().collect())
+            } else {
+                Container::Bitmap(Bitmap::from_words(self.words()))
+            };
+        }
+    }
+
+    fn runs(&self) -> Vec<Run> {
+        let mut runs: Vec<Run> = vec![];
+        for v in self.iter() {
+            match runs.last_mut() {
+                Some(run) if run.last as u32 + 1 == v as u32 => run.last = v,
+                _ => runs.push(Run { start: v, last: v }),
+            }
+        }
+        runs
+    }
+
+    fn union(&self, other: &Container) -> Container {
+        match (self, other) {
+            (Container::Array(a), Container::Array(b)) if a.len() + b.len() <= ARRAY_MAX => {
+                let mut values = Vec::with_capacity(a.len() + b.len());
+                let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+                loop {
+                    let next = match (a.peek(), b.peek()) {
+                        (Some(x), Some(y)) if x < y => a.next(),
+                        (Some(x), Some(y)) if x > y => b.next(),
+                        (Some(_), Some(_)) => {
+                            b.next();
+                            a.next()
+                        }
+                        (Some(_), None) => a.next(),
+                        (None, Some(_)) => b.next(),
+                        (None, None) => break,
+                    };
+                    values.extend(next);
+                }
+                Container::Array(values)
+            }
+            _ => {
+                let mut words = self.words();
+                for (w, o) in words.iter_mut().zip(other.words()) {
+                    *w |= o;
+                }
+                Container::from_words(words)
+            }
+        }
+    }
+
+    fn intersection(&self, other: &Container) -> Container {
+        match (self, other) {
+            (Container::Array(a), _) => {
+                Container::Array(a.iter().copied().filter(|&v| other.contains(v)).collect())
+            }
+            (_, Container::Array(b)) => {
+                Container::Array(b.iter().copied().filter(|&v| self.contains(v)).collect())
+            }
+            _ => {
+                let mut words = self.words();
+                for (w, o) in words.iter_mut().zip(other.words()) {
+                    *w &= o;
+                }
+                Container::from_words(words)
+            }
+        }
+    }
+
+    fn difference(&self, other: &Container) -> Container {
+        match self {
+            Container::Array(a) => {
+                Container::Array(a.iter().copied().filter(|&v| !other.contains(v)).collect())
+            }
+            _ => {
+                let mut words = self.words();
+                for (w, o) in words.iter_mut().zip(other.words()) {
+                    *w &= !o;
+                }
+                Container::from_words(words)
+            }
+        }
+    }
+}
+
+fn array_size(len: usize) -> usize {
+    2 * len
+}
+
+fn run_size(runs: usize) -> usize {
+    2 + 4 * runs
+}
+
+fn insert_into_runs(runs: &mut Vec<Run>, v: u16) -> bool {
+    let index = runs.partition_point(|run| run.start <= v);
+
+    if index > 0 && v <= runs[index - 1].last {
+        return false;
+    }
+
+    let extends_prev = index > 0 && runs[index - 1].last as u32 + 1 == v as u32;
+    let extends_next = index < runs.len() && runs[index].start as u32 == v as u32 + 1;
+
+    match (extends_prev, extends_next) {
+        (true, true) => {
+            runs[index - 1].last = runs[index].last;
+            runs.remove(index);
+        }
+        (true, false) => runs[index - 1].last = v,
+        (false, true) => runs[index].start = v,
+        (false, false) => runs.insert(index, Run { start: v, last: v }),
+    }
+
+    true
+}
+
+fn remove_from_runs(runs: &mut Vec<Run>, v: u16) -> bool {
+    let index = runs.partition_point(|run| run.start <= v);
+
+    if index == 0 || v > runs[index - 1].last {
+        return false;
+    }
+
+    let run = &mut runs[index - 1];
+    match (run.start == v, run.last == v) {
+        (true, true) => {
+            runs.remove(index - 1);
+        }
+        (true, false) => run.start += 1,
+        (false, true) => run.last -= 1,
+        (false, false) => {
+            let tail = Run {
+                start: v + 1,
+                last: run.last,
+            };
+            run.last = v - 1;
+            runs.insert(index, tail);
+        }
+    }
+
+    true
+}
+
+enum ContainerIter<'a> {
+    Array(std::slice::Iter<'a, u16>),
+    Bitmap {
+        words: &'a [u64; BITMAP_WORDS],
+        index: usize,
+        word: u64,
+    },
+    Run {
+        runs: std::slice::Iter<'a, Run>,
+        current: Option<std::ops::RangeInclusive<u16>>,
+    },
+}
+
+impl Iterator for ContainerIter<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        match self {
+            ContainerIter::Array(values) => values.next().copied(),
+            ContainerIter::Bitmap { words, index, word } => loop {
+                if *word != 0 {
+                    let bit = word.trailing_zeros();
+                    *word &= *word - 1;
+                    return Some((*index * 64) as u16 + bit as u16);
+                }
+                *index += 1;
+                *word = *words.get(*index)?;
+            },
+            ContainerIter::Run { runs, current } => loop {
+                if let Some(v) = current.as_mut().and_then(Iterator::next) {
+                    return Some(v);
+                }
+                let run = runs.next()?;
+                *current = Some(run.start..=run.last);
+            },
+        }
+    }
+}
+
+/// An iterator over the values of an [`Id30Set`] in ascending order.
+///
+/// This `struct` is created by [`Id30Set::iter`].
+pub struct Id30SetIter<'a> {
+    keys: std::slice::Iter<'a, u16>,
+    containers: std::slice::Iter<'a, Container>,
+    current: Option<(u16, ContainerIter<'a>)>,
+}
+
+impl Iterator for Id30SetIter<'_> {
+    type Item = Id30;
+
+    fn next(&mut self) -> Option<Id30> {
+        loop {
+            if let Some((key, values)) = &mut self.current {
+                if let Some(low) = values.next() {
+                    return Some(join(*key, low));
+                }
+            }
+            self.current = Some((*self.keys.next()?, self.containers.next()?.iter()));
+        }
+    }
+}
+
+impl Id30Set {
+    /// Create an empty set
+    pub fn new() -> Id30Set {
+        Id30Set::default()
+    }
+
+    /// The number of values in the set
+    pub fn len(&self) -> usize {
+        self.containers.iter().map(Container::len).sum()
+    }
+
+    /// `true` iff the set contains no values
+    pub fn is_empty(&self) -> bool {
+        self.containers.is_empty()
+    }
+
+    /// Remove all values from the set
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.containers.clear();
+    }
+
+    /// `true` iff the set contains `id`
+    pub fn contains(&self, id: Id30) -> bool {
+        let (key, low) = split(id);
+        match self.keys.binary_search(&key) {
+            Ok(index) => self.containers[index].contains(low),
+            Err(_) => false,
+        }
+    }
+
+    /// Add `id` to the set. Returns `true` iff `id` was not already present.
+    pub fn insert(&mut self, id: Id30) -> bool {
+        let (key, low) = split(id);
+        match self.keys.binary_search(&key) {
+            Ok(index) => self.containers[index].insert(low),
+            Err(index) => {
+                self.keys.insert(index, key);
+                self.containers.insert(index, Container::Array(vec![low]));
+                true
+            }
+        }
+    }
+
+    /// Remove `id` from the set. Returns `true` iff `id` was present.
+    pub fn remove(&mut self, id: Id30) -> bool {
+        let (key, low) = split(id);
+        let Ok(index) = self.keys.binary_search(&key) else {
+            return false;
+        };
+
+        let removed = self.containers[index].remove(low);
+        if self.containers[index].len() == 0 {
+            self.keys.remove(index);
+            self.containers.remove(index);
+        }
+        removed
+    }
+
+    /// The number of values in the set that are less than `id`.
+    ///
+    /// For values in the set, this is the index of the value in the iteration
+    /// order, so `set.select(set.rank(id)) == Some(id)` iff `set.contains(id)`.
+    pub fn rank(&self, id: Id30) -> usize {
+        let (key, low) = split(id);
+        let index = self.keys.partition_point(|&k| k < key);
+        let below: usize = self.containers[..index].iter().map(Container::len).sum();
+        match self.keys.get(index) {
+            Some(&k) if k == key => below + self.containers[index].rank(low),
+            _ => below,
+        }
+    }
+
+    /// The `n`th smallest value in the set, counting from zero, or `None` if
+    /// the set contains `n` or fewer values
+    pub fn select(&self, mut n: usize) -> Option<Id30> {
+        for (&key, container) in self.keys.iter().zip(&self.containers) {
+            let len = container.len();
+            if n < len {
+                return container.select(n).map(|low| join(key, low));
+            }
+            n -= len;
+        }
+        None
+    }
+
+    /// An iterator over the values of the set in ascending order
+    pub fn iter(&self) -> Id30SetIter<'_> {
+        Id30SetIter {
+            keys: self.keys.iter(),
+            containers: self.containers.iter(),
+            current: None,
+        }
+    }
+
+    /// The set of values that are in `self` or `other`, or both
+    pub fn union(&self, other: &Id30Set) -> Id30Set {
+        self.merge(other, true, true, Container::union)
+    }
+
+    /// The set of values that are in both `self` and `other`
+    pub fn intersection(&self, other: &Id30Set) -> Id30Set {
+        self.merge(other, false, false, Container::intersection)
+    }
+
+    /// The set of values that are in `self` but not in `other`
+    pub fn difference(&self, other: &Id30Set) -> Id30Set {
+        self.merge(other, true, false, Container::difference)
+    }
+
+    fn merge(
+        &self,
+        other: &Id30Set,
+        keep_self: bool,
+        keep_other: bool,
+        op: fn(&Container, &Container) -> Container,
+    ) -> Id30Set {
+        let mut result = Id30Set::new();
+        let mut push = |key, container: Container| {
+            if container.len() != 0 {
+                result.keys.push(key);
+                result.containers.push(container);
+            }
+        };
+
+        let (mut i, mut j) = (0, 0);
+        while i < self.keys.len() || j < other.keys.len() {
+            // Keys never exceed MAX_KEY, so u16::MAX sorts after all of them
+            let a = self.keys.get(i).copied().unwrap_or(u16::MAX);
+            let b = other.keys.get(j).copied().unwrap_or(u16::MAX);
+            match a.cmp(&b) {
+                Ordering::Equal => {
+                    push(a, op(&self.containers[i], &other.containers[j]));
+                    i += 1;
+                    j += 1;
+                }
+                Ordering::Less => {
+                    if keep_self {
+                        push(a, self.containers[i].clone());
+                    }
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    if keep_other {
+                        push(b, other.containers[j].clone());
+                    }
+                    j += 1;
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Convert each chunk of the set to its most compact representation,
+    /// including the run-length representation which is otherwise not used.
+    ///
+    /// This is worthwhile for sets with many consecutive values, after the
+    /// set has been populated.
+    pub fn optimize(&mut self) {
+        self.containers.iter_mut().for_each(Container::optimize);
+    }
+
+    /// The number of bytes written by [`Id30Set::serialize_into`]
+    pub fn serialized_size(&self) -> usize {
+        let has_runs = self.has_runs();
+        let n = self.containers.len();
+
+        let cookie_size = if has_runs { 4 + n.div_ceil(8) } else { 8 };
+        let header_size = 4 * n;
+        let offsets_size = if !has_runs || n >= NO_OFFSET_THRESHOLD {
+            4 * n
+        } else {
+            0
+        };
+        let data_size: usize = self
+            .containers
+            .iter()
+            .map(|container| match container {
+                Container::Array(values) => array_size(values.len()),
+                Container::Bitmap(_) => array_size(ARRAY_MAX),
+                Container::Run(runs) => run_size(runs.len()),
+            })
+            .sum();
+
+        cookie_size + header_size + offsets_size + data_size
+    }
+
+    fn has_runs(&self) -> bool {
+        self.containers
+            .iter()
+            .any(|container| matches!(container, Container::Run(_)))
+    }
+
+    /// Write the set to `writer` in the portable Roaring format
+    pub fn serialize_into<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let has_runs = self.has_runs();
+        let n = self.containers.len();
+
+        if has_runs {
+            writer.write_all(&SERIAL_COOKIE.to_le_bytes())?;
+            writer.write_all(&((n - 1) as u16).to_le_bytes())?;
+            let mut run_flags = vec![0u8; n.div_ceil(8)];
+            for (index, container) in self.containers.iter().enumerate() {
+                if let Container::Run(_) = container {
+                    run_flags[index / 8] |= 1 << (index % 8);
+                }
+            }
+            writer.write_all(&run_flags)?;
+        } else {
+            writer.write_all(&SERIAL_COOKIE_NO_RUNS.to_le_bytes())?;
+            writer.write_all(&(n as u32).to_le_bytes())?;
+        }
+
+        for (&key, container) in self.keys.iter().zip(&self.containers) {
+            writer.write_all(&key.to_le_bytes())?;
+            writer.write_all(&((container.len() - 1) as u16).to_le_bytes())?;
+        }
+
+        if !has_runs || n >= NO_OFFSET_THRESHOLD {
+            let mut offset = if has_runs { 4 + n.div_ceil(8) } else { 8 } + 8 * n;
+            for container in &self.containers {
+                writer.write_all(&(offset as u32).to_le_bytes())?;
+                offset += match container {
+                    Container::Array(values) => array_size(values.len()),
+                    Container::Bitmap(_) => array_size(ARRAY_MAX),
+                    Container::Run(runs) => run_size(runs.len()),
+                };
+            }
+        }
+
+        for container in &self.containers {
+            match container {
+                Container::Array(values) => {
+                    for v in values {
+                        writer.write_all(&v.to_le_bytes())?;
+                    }
+                }
+                Container::Bitmap(bitmap) => {
+                    for w in &bitmap.words {
+                        writer.write_all(&w.to_le_bytes())?;
+                    }
+                }
+                Container::Run(runs) => {
+                    writer.write_all(&(runs.len() as u16).to_le_bytes())?;
+                    for run in runs {
+                        writer.write_all(&run.start.to_le_bytes())?;
+                        writer.write_all(&(run.last - run.start).to_le_bytes())?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read a set in the portable Roaring format from `reader`.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the input is not well
+    /// formed, or if it contains values that are out of range for [`Id30`].
+    pub fn deserialize_from<R: Read>(mut reader: R) -> io::Result<Id30Set> {
+        fn invalid(msg: &str) -> io::Error {
+            io::Error::new(io::ErrorKind::InvalidData, msg)
+        }
+
+        fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+            let mut buf = [0; 2];
+            reader.read_exact(&mut buf)?;
+            Ok(u16::from_le_bytes(buf))
+        }
+
+        fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+            let mut buf = [0; 4];
+            reader.read_exact(&mut buf)?;
+            Ok(u32::from_le_bytes(buf))
+        }
+
+        let cookie = read_u32(&mut reader)?;
+        let (n, run_flags) = if cookie == SERIAL_COOKIE_NO_RUNS {
+            (read_u32(&mut reader)? as usize, None)
+        } else if cookie as u16 == SERIAL_COOKIE {
+            let n = (cookie >> 16) as usize + 1;
+            let mut run_flags = vec![0u8; n.div_ceil(8)];
+            reader.read_exact(&mut run_flags)?;
+            (n, Some(run_flags))
+        } else {
+            return Err(invalid("unknown cookie for Roaring format"));
+        };
+
+        if n > MAX_KEY as usize + 1 {
+            return Err(invalid("too many containers for Id30Set"));
+        }
+
+        let mut header = Vec::with_capacity(n);
+        for _ in 0..n {
+            let key = read_u16(&mut reader)?;
+            let len = read_u16(&mut reader)? as usize + 1;
+            if key > MAX_KEY {
+                return Err(invalid("value out of range for Id30"));
+            }
+            if header.last().is_some_and(|&(prev, _)| prev >= key) {
+                return Err(invalid("container keys are not strictly increasing"));
+            }
+            header.push((key, len));
+        }
+
+        if run_flags.is_none() || n >= NO_OFFSET_THRESHOLD {
+            // The offsets are redundant when reading sequentially
+            for _ in 0..n {
+                read_u32(&mut reader)?;
+            }
+        }
+
+        let mut set = Id30Set::new();
+        for (index, (key, len)) in header.into_iter().enumerate() {
+            let is_run = run_flags
+                .as_ref()
+                .is_some_and(|flags| flags[index / 8] & (1 << (index % 8)) != 0);
+
+            let container = if is_run {
+                let count = read_u16(&mut reader)? as usize;
+                let mut runs: Vec<Run> = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let start = read_u16(&mut reader)?;
+                    let last = start
+                        .checked_add(read_u16(&mut reader)?)
+                        .ok_or_else(|| invalid("run overflows container"))?;
+                    if runs
+                        .last()
+                        .is_some_and(|prev| prev.last as u32 + 1 >= start as u32)
+                    {
+                        return Err(invalid("runs are not sorted and disjoint"));
+                    }
+                    runs.push(Run { start, last });
+                }
+                Container::Run(runs)
+            } else if len <= ARRAY_MAX {
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    let v = read_u16(&mut reader)?;
+                    if values.last().is_some_and(|&prev| prev >= v) {
+                        return Err(invalid("values are not strictly increasing"));
+                    }
+                    values.push(v);
+                }
+                Container::Array(values)
+            } else {
+                let mut words = [0; BITMAP_WORDS];
+                for w in &mut words {
+                    let mut buf = [0; 8];
+                    reader.read_exact(&mut buf)?;
+                    *w = u64::from_le_bytes(buf);
+                }
+                Container::Bitmap(Bitmap::from_words(words))
+            };
+
+            if container.len() != len {
+                return Err(invalid("container cardinality does not match header"));
+            }
+
+            set.keys.push(key);
+            set.containers.push(container);
+        }
+
+        Ok(set)
+    }
+}
+
+impl PartialEq for Id30Set {
+    fn eq(&self, other: &Id30Set) -> bool {
+        self.keys == other.keys
+            && self
+                .containers
+                .iter()
+                .zip(&other.containers)
+                .all(|(a, b)| a.len() == b.len() && a.iter().eq(b.iter()))
+    }
+}
+
+impl Eq for Id30Set {}
+
+impl fmt::Debug for Id30Set {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a Id30Set {
+    type Item = Id30;
+    type IntoIter = Id30SetIter<'a>;
+
+    fn into_iter(self) -> Id30SetIter<'a> {
+        self.iter()
+    }
+}
+
+impl Extend<Id30> for Id30Set {
+    fn extend<T: IntoIterator<Item = Id30>>(&mut self, iter: T) {
+        for id in iter {
+            self.insert(id);
+        }
+    }
+}
+
+impl FromIterator<Id30> for Id30Set {
+    fn from_iter<T: IntoIterator<Item = Id30>>(iter: T) -> Id30Set {
+        let mut set = Id30Set::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const N: usize> From<[Id30; N]> for Id30Set {
+    fn from(ids: [Id30; N]) -> Id30Set {
+        ids.into_iter().collect()
+    }
+}
+
+impl BitOr<&Id30Set> for &Id30Set {
+    type Output = Id30Set;
+
+    fn bitor(self, rhs: &Id30Set) -> Id30Set {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd<&Id30Set> for &Id30Set {
+    type Output = Id30Set;
+
+    fn bitand(self, rhs: &Id30Set) -> Id30Set {
+        self.intersection(rhs)
+    }
+}
+
+impl Sub<&Id30Set> for &Id30Set {
+    type Output = Id30Set;
+
+    fn sub(self, rhs: &Id30Set) -> Id30Set {
+        self.difference(rhs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn id(x: u32) -> Id30 {
+        Id30::try_from(x).unwrap()
+    }
+
+    /// Values spread over sparse, dense and clustered chunks
+    fn sample() -> Vec<Id30> {
+        let sparse = (0..100).map(|x| x * 7919);
+        let dense = (0..60000).map(|x| (20 << 16) + x + x / 3);
+        let clustered = (0..1000).flat_map(|x| (x * 100..x * 100 + 20).map(|y| (40 << 16) + y));
+        let top = [(1 << 30) - 2, (1 << 30) - 1];
+        sparse
+            .chain(dense)
+            .chain(clustered)
+            .chain(top)
+            .map(id)
+            .collect()
+    }
+
+    #[test]
+    fn insert_contains_remove() {
+        let mut set = Id30Set::new();
+        assert!(set.insert(id(1234)));
+        assert!(!set.insert(id(1234)));
+        assert!(set.contains(id(1234)));
+        assert!(!set.contains(id(1235)));
+        assert_eq!(set.len(), 1);
+
+        assert!(set.remove(id(1234)));
+        assert!(!set.remove(id(1234)));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn matches_btreeset() {
+        let reference: BTreeSet<Id30> = sample().into_iter().collect();
+        let mut set: Id30Set = sample().into_iter().collect();
+
+        assert_eq!(set.len(), reference.len());
+        assert!(set.iter().eq(reference.iter().copied()));
+
+        set.optimize();
+        let clustered = set.keys.binary_search(&40).unwrap();
+        assert!(matches!(set.containers[clustered], Container::Run(_)));
+        assert_eq!(set.len(), reference.len());
+        assert!(set.iter().eq(reference.iter().copied()));
+
+        for x in [
+            0,
+            7919,
+            7920,
+            (20 << 16) + 3,
+            (20 << 16) + 4,
+            (40 << 16) + 119,
+        ] {
+            assert_eq!(set.contains(id(x)), reference.contains(&id(x)), "{x}");
+        }
+    }
+
+    #[test]
+    fn switches_representation() {
+        let mut set: Id30Set = (0..ARRAY_MAX as u32).map(|x| id(x * 2)).collect();
+        assert!(matches!(set.containers[0], Container::Array(_)));
+
+        set.insert(id(1));
+        assert!(matches!(set.containers[0], Container::Bitmap(_)));
+
+        set.remove(id(1));
+        assert!(matches!(set.containers[0], Container::Array(_)));
+
+        let mut set: Id30Set = (0..10000).map(id).collect();
+        set.optimize();
+        assert!(matches!(set.containers[0], Container::Run(_)));
+
+        set.remove(id(5000));
+        assert_eq!(set.containers[0].run_count(), 2);
+        set.insert(id(5000));
+        assert_eq!(set.containers[0].run_count(), 1);
+        assert_eq!(set.len(), 10000);
+    }
+
+    #[test]
+    fn rank_select() {
+        let reference: Vec<Id30> = sample();
+        let mut sorted = reference.clone();
+        sorted.sort();
+        sorted.dedup();
+
+        let mut set: Id30Set = reference.into_iter().collect();
+        for _ in 0..2 {
+            for (index, &x) in sorted.iter().enumerate().step_by(97) {
+                assert_eq!(set.rank(x), index);
+                assert_eq!(set.select(index), Some(x));
+            }
+            assert_eq!(set.rank(id(7918)), 1);
+            assert_eq!(set.rank(id((40 << 16) + 25)), 100 + 60000 + 20);
+            assert_eq!(set.select(sorted.len()), None);
+
+            set.optimize();
+        }
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: Id30Set = sample().into_iter().step_by(2).collect();
+        let mut b: Id30Set = sample().into_iter().step_by(3).collect();
+        b.optimize();
+
+        let ra: BTreeSet<Id30> = a.iter().collect();
+        let rb: BTreeSet<Id30> = b.iter().collect();
+
+        assert!((&a | &b).iter().eq(ra.union(&rb).copied()));
+        assert!((&a & &b).iter().eq(ra.intersection(&rb).copied()));
+        assert!((&a - &b).iter().eq(ra.difference(&rb).copied()));
+        assert!((&b - &a).iter().eq(rb.difference(&ra).copied()));
+
+        assert_eq!(&a - &a, Id30Set::new());
+        assert_eq!(&a & &b, &b & &a);
+    }
+
+    #[test]
+    fn serialize_portable_format() -> io::Result<()> {
+        let set = Id30Set::from([id(1), id(2), id(3)]);
+
+        let mut buf = vec![];
+        set.serialize_into(&mut buf)?;
+
+        #[rustfmt::skip]
+        assert_eq!(buf, [
+            0x3a, 0x30, 0, 0, // cookie
+            1, 0, 0, 0, // number of containers
+            0, 0, 2, 0, // key, cardinality - 1
+            16, 0, 0, 0, // offset
+            1, 0, 2, 0, 3, 0, // values
+        ]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn serialize_roundtrip() -> io::Result<()> {
+        let mut set: Id30Set = sample().into_iter().collect();
+
+        for _ in 0..2 {
+            let mut buf = vec![];
+            set.serialize_into(&mut buf)?;
+            assert_eq!(buf.len(), set.serialized_size());
+
+            let deserialized = Id30Set::deserialize_from(buf.as_slice())?;
+            assert_eq!(deserialized, set);
+
+            set.optimize();
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_out_of_range() {
+        #[rustfmt::skip]
+        let buf = [
+            0x3a, 0x30, 0, 0,
+            1, 0, 0, 0,
+            0, 0x40, 0, 0, // key 0x4000 holds values from 1 << 30
+            16, 0, 0, 0,
+            0, 0,
+        ];
+
+        let err = Id30Set::deserialize_from(buf.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -30,6 +30,18 @@
 //! integral and Id30 representations, and for generating new IDs on the command
 //! line.
 //!
+//! For keeping large numbers of IDs in memory, [`Id30Set`] is a compact set
+//! representation that exploits the bounded value space. For hash tables keyed
+//! by `Id30`, [`Id30BuildHasher`] avoids the cost of hashing values that are
+//! already random.
+//!
+//! For finding IDs mentioned in free text, such as support tickets and chat
+//! logs, see [`find_iter`] and [`Finder`].
+//!
+//! For working with Id30 values in the database, such as showing Id30 strings
+//! in ad-hoc queries and views, [`SqlDialect`] generates SQL functions and
+//! constraints for Postgres, MySQL and SQLite.
+//!
 //! # Id30 Encoding
 //! The Id30 encoding is a case-insensitive base 32 encoding that handles some
 //! confusable characters to compensate for some common misreadings and
@@ -134,6 +146,26 @@
 //!  - `rand08` (alias `rand`), for integration with `rand` 0.8.z
 //!  - `serde1` (alias `serde`), for integration with `serde` 1.y.z
 //!  - `diesel2` (alias `diesel`), for integration with `diesel` 2.y.z
+//!  - `diesel2_sqlite`, `diesel2_postgres` and `diesel2_mysql` (aliases
+//!    `diesel_sqlite`, `diesel_postgres` and `diesel_mysql`), for storing
+//!    `Id30` as text or `BigInt` with the respective backend of `diesel` 2.y.z.
+//!    `diesel2_mysql` additionally enables storing `Id30` as `INT UNSIGNED`
+//!  - `nohash_hasher02` (alias `nohash_hasher`), for integration with
+//!    `nohash-hasher` 0.2.z
+//!  - `axum08` (alias `axum`), for integration with `axum` 0.8.z
+//!  - `actix-web4` (alias `actix-web`), for integration with `actix-web` 4.y.z
+//!  - `tower03` (alias `tower`), for integration with `tower-layer` and
+//!    `tower-service` 0.3.z and `http` 1.y.z
+//!  - `sqlx07` (alias `sqlx`), for integration with `sqlx` 0.7.z
+//!  - `rusqlite030` (alias `rusqlite`), for integration with `rusqlite` 0.30.z
+//!  - `postgres_types02` (alias `postgres_types`), for integration with
+//!    `postgres-types` 0.2.z, as used by `tokio-postgres` and `postgres`
+//!  - `schemars1` (alias `schemars`), for integration with `schemars` 1.y.z
+//!  - `utoipa5` (alias `utoipa`), for integration with `utoipa` 5.y.z
+//!  - `bincode2` (alias `bincode`), for integration with `bincode` 2.y.z
+//!  - `borsh1` (alias `borsh`), for integration with `borsh` 1.y.z
+//!  - `proptest1` (alias `proptest`), for integration with `proptest` 1.y.z
+//!  - `arbitrary1` (alias `arbitrary`), for integration with `arbitrary` 1.y.z
 //!
 //! See [`Id30`] for details about each integration.
 
@@ -150,24 +182,71 @@
     feature(stdarch_x86_avx512)
 )]
 
+mod actix_web_support;
+mod arbitrary_support;
+mod axum_support;
+mod bincode_support;
+mod borsh_support;
+mod canonical_id30;
+mod canonical_location;
 mod codec_tables;
+mod crockford;
 mod diesel_support;
 mod display;
+mod find;
 mod from;
 mod from_str;
+mod hash;
+mod id30_index;
 mod id30_parse;
+mod id30_set;
+mod percent_decode;
+mod phonetic;
+mod postgres_types_support;
+pub mod proptest;
 mod rand;
+mod rusqlite_support;
+mod schema;
+mod schemars_support;
+pub mod serde;
 mod serde_support;
+mod sql;
+mod sqlx_support;
+mod tower_support;
+mod utoipa_support;
+mod word_list;
+mod words;
 
 use std::fmt::Debug;
 
+#[cfg(feature = "axum08")]
+pub use axum_support::Id30Path;
+pub use canonical_id30::{CanonicalId30, CanonicalParseError};
+#[cfg(any(feature = "axum08", feature = "actix-web4"))]
+pub use canonical_location::Id30PathRejection;
+pub use crockford::{Crockford, DetectedAlphabet};
+#[cfg(all(feature = "diesel2", feature = "rand08"))]
+pub use diesel_support::insert_with_random_id;
+pub use find::{find_iter, replace_all, Finder, Id30Match, Id30Matches, REGEX};
 pub use from::OutOfRangeError;
+pub use hash::{Id30BuildHasher, Id30HashSet, Id30Hasher, Id30Map};
+pub use id30_index::{Id30Index, Suggestion};
 pub use id30_parse::{Id30Parse, ParseError};
+pub use id30_set::{Id30Set, Id30SetIter};
+#[cfg(feature = "rusqlite030")]
+pub use rusqlite_support::register_sqlite_functions;
+pub use sql::{SqlDialect, UnknownDialectError};
+#[cfg(feature = "tower03")]
+pub use tower_support::{CanonicalRedirect, CanonicalRedirectFuture, CanonicalRedirectLayer};
 
+// An extern crate alias, rather than a use alias, makes `diesel` resolve in
+// the code generated by diesel's macros, also in submodules
 #[cfg(feature = "diesel2")]
-use diesel2 as diesel;
+extern crate diesel2 as diesel;
+#[cfg(feature = "diesel2_mysql")]
+use diesel2::sql_types::Unsigned;
 #[cfg(feature = "diesel2")]
-use diesel2::sql_types::Integer;
+use diesel2::sql_types::{BigInt, Integer, Text};
 
 /// An implementation of the Id30 encoding scheme as documented at the [crate]
 /// root.
@@ -183,29 +262,29 @@ use diesel2::sql_types::Integer;
 ///
 /// There are many ways to create instances of `Id30`:
 ///  - Via the `TryFrom` trait from either a `u32` or an `i32`:
-///     ```rust
-///     # use id30::Id30;
-///     let id: Id30 = 1234.try_into().expect("1234 is in range");
-///     assert_eq!(Id30::try_from(1 << 31), Err(id30::OutOfRangeError));
-///     ```
+///    ```rust
+///    # use id30::Id30;
+///    let id: Id30 = 1234.try_into().expect("1234 is in range");
+///    assert_eq!(Id30::try_from(1 << 31), Err(id30::OutOfRangeError));
+///    ```
 ///  - Parsing, via the `FromStr` trait:
-///     ```rust
-///     # use id30::Id30;
-///     let id: id30::Id30 = "hrga2q".parse().unwrap();
-///     ```
-///     **Tip:** Parse to the [`Id30Parse`] type if you need to know whether the
-///     text representation is canonical or alternate.
+///    ```rust
+///    # use id30::Id30;
+///    let id: id30::Id30 = "hrga2q".parse().unwrap();
+///    ```
+///    **Tip:** Parse to the [`Id30Parse`] type if you need to know whether the
+///    text representation is canonical or alternate.
 ///  - With feature `rand`, via the [`Distribution`][rand08::distributions::Distribution]
-///     trait:
-///     ```rust
-///     # use id30::Id30;
-///     # use rand08 as rand;
-///     use rand::{distributions::Standard, prelude::*};
-///     let mut rng = rand::thread_rng();
-///
-///     let id: Id30 = rng.gen();
-///     let ids: Vec<Id30> = Standard.sample_iter(rng).take(10).collect();
-///     ```
+///    trait:
+///    ```rust
+///    # use id30::Id30;
+///    # use rand08 as rand;
+///    use rand::{distributions::Standard, prelude::*};
+///    let mut rng = rand::thread_rng();
+///
+///    let id: Id30 = rng.gen();
+///    let ids: Vec<Id30> = Standard.sample_iter(rng).take(10).collect();
+///    ```
 ///  - With feature `serde`, via deserialization
 ///  - With feature `diesel`, as output from queries
 ///
@@ -218,33 +297,159 @@ use diesel2::sql_types::Integer;
 ///
 /// To generate the text representation:
 ///  - format an `Id30` value via the `Display` trait:
-///     ```rust
-///     # use id30::Id30;
-///     let id: id30::Id30 = "j9yceq".parse().unwrap();
-///     assert_eq!(&id.to_string(), "j9yceq");
-///     assert_eq!(&format!("/path/to/{id}"), "/path/to/j9yceq");
-///     ```
+///    ```rust
+///    # use id30::Id30;
+///    let id: id30::Id30 = "j9yceq".parse().unwrap();
+///    assert_eq!(&id.to_string(), "j9yceq");
+///    assert_eq!(&format!("/path/to/{id}"), "/path/to/j9yceq");
+///    ```
 ///  - or, with feature `serde`, via serialization
 ///
+/// For reading IDs out loud, there are alternative text representations via
+/// [`Id30::to_phonetic`] and [`Id30::to_words`], which can be parsed with
+/// [`Id30Parse::from_phonetic`] and [`Id30Parse::from_words`] respectively.
+///
+/// For interoperability with systems that use Crockford's base 32 encoding,
+/// see [`Id30::to_crockford`], [`Id30Parse::from_crockford`] and the
+/// [`Crockford`] adapter type. [`DetectedAlphabet`] parses strings that may be
+/// in either encoding.
+///
 /// # Integrations with other crates
 ///  - crate `rand` via feature `rand08` (alias `rand`):
 ///
-///     `Id30` implements [`Distribution`], enabling generation of random `Id30`
-///     values.
+///    `Id30` implements [`Distribution`], enabling generation of random `Id30`
+///    values.
 ///
 ///  - crate `serde` via feature `serde1` (alias `serde`):
 ///
-///     `Id30` implements [`Serialize`] and [`Deserialize`], enabling
-///     serialization and deserialization of `Id30` values as text through
-///     serde.
+///    `Id30` implements [`Serialize`] and [`Deserialize`], enabling
+///    serialization and deserialization of `Id30` values through serde, as
+///    text in human readable formats and as `u32` in other formats.
+///    Deserialization from human readable formats also accepts integers. See
+///    [`crate::serde`] for adapters for other forms. [`Id30Parse`] is
+///    serialized as a struct that keeps the canonicality flag, and
+///    [`CanonicalId30`] rejects non-canonical Id30 strings.
 ///
 ///  - crate `diesel` via feature `diesel2` (alias `diesel`):
 ///
-///     `Id30` implements [`FromSql`] and [`Queryable`], enabling
-///     deserialization of integers from the database as `Id30` values, and
-///     [`ToSql`], enabling serialization of `Id30` values as integers in the
-///     database. Additionally, [`AsExpression`] is implemented, enabling the
-///     usage of `Id30` values in diesel query builder expressions.
+///    `Id30` implements [`FromSql`] and [`Queryable`], enabling
+///    deserialization of integers from the database as `Id30` values, and
+///    [`ToSql`], enabling serialization of `Id30` values as integers in the
+///    database. Additionally, [`AsExpression`] is implemented, enabling the
+///    usage of `Id30` values in diesel query builder expressions.
+///
+///    `Id30` can also be stored in `BigInt` columns, and, with feature
+///    `diesel2_mysql`, in `Unsigned<Integer>` columns, with the same range
+///    validation when reading. Writing `BigInt` requires the feature for the
+///    backend, `diesel2_sqlite`, `diesel2_postgres` or `diesel2_mysql`.
+///
+///    `Id30` can also be stored in `Text` columns, in which case it is
+///    always written in the canonical encoding. Writing text requires the
+///    feature for the backend, `diesel2_sqlite`, `diesel2_postgres` or
+///    `diesel2_mysql`. When reading, `Id30` accepts
+///    non-canonical encodings, [`Id30Parse`] reports them via
+///    `is_canonical` and [`CanonicalId30`] rejects them. Since the encoding
+///    preserves ordering, `ORDER BY` on a `Text` column gives the same order
+///    as `Ord for Id30`, as long as the column uses a collation that orders
+///    digits before lower case letters, such as the binary or `C` collation.
+///
+///    With feature `rand08` also enabled, [`insert_with_random_id`] inserts
+///    rows with random `Id30` primary keys, retrying on collisions.
+///
+///  - crate `nohash-hasher` via feature `nohash_hasher02` (alias
+///    `nohash_hasher`):
+///
+///    `Id30` implements [`IsEnabled`], enabling its use as a key with the
+///    pass-through hashers of `nohash-hasher`.
+///
+///  - crate `axum` via feature `axum08` (alias `axum`):
+///
+///    [`Id30Path`] implements [`FromRequestParts`], enabling extraction of
+///    `Id30` values from request paths, with redirection of non-canonical
+///    encodings to the canonical URL.
+///
+///  - crate `actix-web` via feature `actix-web4` (alias `actix-web`):
+///
+///    `Id30` and [`Id30Parse`] implement [`FromRequest`], enabling extraction
+///    from request paths. Extracting `Id30` redirects non-canonical encodings
+///    to the canonical URL.
+///
+///  - crates `tower-layer`, `tower-service` and `http` via feature `tower03`
+///    (alias `tower`):
+///
+///    [`CanonicalRedirectLayer`] implements [`Layer`], enabling redirection
+///    of requests for non-canonical encodings to the canonical URL for any
+///    service built on the `http` crate types.
+///
+///  - crate `sqlx` via feature `sqlx07` (alias `sqlx`):
+///
+///    `Id30` implements [`Type`], [`Encode`] and [`Decode`] for all databases
+///    where `i32` does, enabling storage of `Id30` values as integers, for
+///    example as `INT4` in Postgres, `INTEGER` in SQLite and `INT` in MySQL.
+///
+///  - crate `rusqlite` via feature `rusqlite030` (alias `rusqlite`):
+///
+///    `Id30` implements [`ToSql`][rusqlite030::types::ToSql] and
+///    [`FromSql`][rusqlite030::types::FromSql], enabling storage of `Id30`
+///    values as integers in SQLite. Additionally,
+///    [`register_sqlite_functions`] registers SQL functions for encoding and
+///    decoding Id30 strings in queries.
+///
+///  - crate `postgres-types` via feature `postgres_types02` (alias
+///    `postgres_types`):
+///
+///    `Id30` implements [`ToSql`][postgres_types02::ToSql] and
+///    [`FromSql`][postgres_types02::FromSql], enabling usage of `Id30`
+///    values with `tokio-postgres` and `postgres` for `INT4`, `INT8` and text
+///    columns. Text is written in the canonical encoding, and reading text
+///    accepts non-canonical encodings.
+///
+///  - crate `schemars` via feature `schemars1` (alias `schemars`):
+///
+///    `Id30`, [`CanonicalId30`] and [`Id30Parse`] implement
+///    [`JsonSchema`][schemars1::JsonSchema], describing Id30 strings as
+///    strings of length 6 with a `pattern` matching the valid encodings.
+///    `CanonicalId30` only matches the canonical encoding, and can be used
+///    for `Id30` fields with `#[schemars(with = "id30::CanonicalId30")]`.
+///
+///  - crate `utoipa` via feature `utoipa5` (alias `utoipa`):
+///
+///    `Id30`, [`CanonicalId30`] and [`Id30Parse`] implement
+///    [`ToSchema`][utoipa5::ToSchema] and
+///    [`PartialSchema`][utoipa5::PartialSchema], describing Id30 strings in
+///    OpenAPI documents with an example value, length limits and a `pattern`,
+///    also when used as path parameters in `#[utoipa::path(params(...))]`.
+///    `Id30Parse` is described as an object with the fields `id30` and
+///    `is_canonical`, like it is serialized.
+///
+///  - crate `bincode` via feature `bincode2` (alias `bincode`):
+///
+///    `Id30` implements [`Encode`][bincode2::Encode],
+///    [`Decode`][bincode2::Decode] and
+///    [`BorrowDecode`][bincode2::BorrowDecode], encoding `Id30` values as
+///    `u32`, which is a varint or 4 bytes depending on the configuration.
+///    Decoding fails for values that are out of range for `Id30`.
+///
+///  - crate `borsh` via feature `borsh1` (alias `borsh`):
+///
+///    `Id30` implements [`BorshSerialize`][borsh1::BorshSerialize] and
+///    [`BorshDeserialize`][borsh1::BorshDeserialize], serializing `Id30`
+///    values as `u32`. Deserialization fails with an
+///    [`InvalidData`][std::io::ErrorKind::InvalidData] error wrapping
+///    [`OutOfRangeError`] for values that are out of range for `Id30`.
+///
+///  - crate `proptest` via feature `proptest1` (alias `proptest`):
+///
+///    `Id30` implements [`Arbitrary`][proptest1::arbitrary::Arbitrary], and
+///    [`id30::proptest`](crate::proptest) offers strategies for canonical,
+///    non-canonical and near-miss invalid Id30 strings.
+///
+///  - crate `arbitrary` via feature `arbitrary1` (alias `arbitrary`):
+///
+///    `Id30` and [`Id30Parse`] implement
+///    [`Arbitrary`][arbitrary1::Arbitrary], for fuzzing code that consumes
+///    Id30 values. Arbitrary `Id30Parse` values come from parsing valid,
+///    possibly non-canonical, encodings.
 ///
 /// [`Distribution`]: rand08::distributions::Distribution
 ///
@@ -255,11 +460,25 @@ use diesel2::sql_types::Integer;
 /// [`Queryable`]: diesel2::deserialize::Queryable
 /// [`ToSql`]: diesel2::serialize::ToSql
 /// [`AsExpression`]: diesel::expression::AsExpression
-
+///
+/// [`IsEnabled`]: nohash_hasher02::IsEnabled
+///
+/// [`FromRequestParts`]: axum08::extract::FromRequestParts
+///
+/// [`FromRequest`]: actix_web4::FromRequest
+///
+/// [`Layer`]: tower_layer03::Layer
+///
+/// [`Type`]: sqlx07::Type
+/// [`Encode`]: sqlx07::Encode
+/// [`Decode`]: sqlx07::Decode
 #[repr(transparent)]
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
 #[cfg_attr(feature = "diesel2", derive(diesel::AsExpression, diesel::FromSqlRow))]
 #[cfg_attr(feature = "diesel2", diesel(sql_type = Integer))]
+#[cfg_attr(feature = "diesel2", diesel(sql_type = Text))]
+#[cfg_attr(feature = "diesel2", diesel(sql_type = BigInt))]
+#[cfg_attr(feature = "diesel2_mysql", diesel(sql_type = Unsigned<Integer>))]
 pub struct Id30(u32);
 
 impl Debug for Id30 {
@@ -0,0 +1,53 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(any(feature = "axum08", feature = "actix-web4", feature = "tower03"))]
+
+//! Percent-decoding of URL path segments for the web framework integrations
+
+use std::borrow::Cow;
+
+fn hex_value(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|x| x as u8)
+}
+
+/// The percent-decoding of `segment`, or `None` if it contains an invalid
+/// escape or does not decode to UTF-8
+pub(crate) fn percent_decode(segment: &str) -> Option<Cow<'_, str>> {
+    if !segment.contains('%') {
+        return Some(Cow::Borrowed(segment));
+    }
+
+    let mut bytes = segment.bytes();
+    let mut decoded = Vec::with_capacity(segment.len());
+    while let Some(c) = bytes.next() {
+        if c == b'%' {
+            let high = bytes.next().and_then(hex_value)?;
+            let low = bytes.next().and_then(hex_value)?;
+            decoded.push(high << 4 | low);
+        } else {
+            decoded.push(c);
+        }
+    }
+    String::from_utf8(decoded).ok().map(Cow::Owned)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        assert_eq!(percent_decode("bpv3uq").as_deref(), Some("bpv3uq"));
+        assert_eq!(percent_decode("%62%70v3uq").as_deref(), Some("bpv3uq"));
+        assert_eq!(percent_decode("%c3%b8").as_deref(), Some("ø"));
+        assert_eq!(percent_decode("%6").as_deref(), None);
+        assert_eq!(percent_decode("%zz").as_deref(), None);
+        assert_eq!(percent_decode("%ff").as_deref(), None);
+    }
+}
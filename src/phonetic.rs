@@ -0,0 +1,206 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{Id30, Id30Parse, ParseError};
+
+/// The spoken form of each symbol, in the same order as the encoding alphabet
+const PHONETIC: [&str; 32] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "alfa",
+    "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "juliett", "kilo", "mike",
+    "november", "papa", "quebec", "romeo", "tango", "uniform", "victor", "whiskey", "x-ray",
+    "yankee", "zulu",
+];
+
+/// Spoken forms of characters that are not in the canonical alphabet, and
+/// common alternative spellings of the canonical words
+const ALTERNATIVES: [(&str, u8); 9] = [
+    ("alpha", b'a'),
+    ("india", b'i'),
+    ("juliet", b'j'),
+    ("lima", b'l'),
+    ("niner", b'9'),
+    ("oscar", b'o'),
+    ("sierra", b's'),
+    ("whisky", b'w'),
+    ("xray", b'x'),
+];
+
+fn lookup(word: &str) -> Option<u8> {
+    let word = word.to_ascii_lowercase();
+
+    PHONETIC
+        .iter()
+        .position(|&w| w == word)
+        .map(|symbol| crate::codec_tables::ENCODE[symbol])
+        .or_else(|| {
+            ALTERNATIVES
+                .iter()
+                .find(|&&(w, _)| w == word)
+                .map(|&(_, c)| c)
+        })
+}
+
+fn push_token(token: &str, chars: &mut String) -> Result<(), ParseError> {
+    if let Some(c) = lookup(token) {
+        chars.push(c as char);
+    } else if token.contains('-') {
+        for part in token.split('-').filter(|part| !part.is_empty()) {
+            push_token(part, chars)?;
+        }
+    } else if token.is_ascii() {
+        // Literal characters, as when a transcript renders "three" as "3".
+        // These are validated when the collected characters are parsed.
+        chars.push_str(token);
+    } else {
+        return Err(ParseError::InvalidCharacters);
+    }
+    Ok(())
+}
+
+impl Id30 {
+    /// Render the Id30 as a sequence of words from the NATO phonetic alphabet,
+    /// for reading it out loud.
+    ///
+    /// Letters are rendered using the ICAO spelling of the code words, and
+    /// digits as their English names.
+    ///
+    /// ```
+    /// # use id30::Id30;
+    /// let id: Id30 = "bpv3uq".parse().unwrap();
+    /// assert_eq!(id.to_phonetic(), "bravo papa victor three uniform quebec");
+    /// ```
+    pub fn to_phonetic(&self) -> String {
+        let words: Vec<&str> = [25, 20, 15, 10, 5, 0]
+            .iter()
+            .map(|shift| PHONETIC[((self.0 >> shift) & 0b1_1111) as usize])
+            .collect();
+        words.join(" ")
+    }
+}
+
+impl Id30Parse {
+    /// Parse a spoken rendering of an Id30, such as the output of
+    /// [`Id30::to_phonetic`] or a speech-to-text transcript of it.
+    ///
+    /// The input is split into words on whitespace and punctuation. Each word
+    /// can be a NATO phonetic code word, the English name of a digit or one or
+    /// more literal characters, and words are matched case-insensitively. Code
+    /// words are also accepted for the confusable characters `i`, `l`, `o` and
+    /// `s`, and for common alternative spellings like "alpha" and "niner".
+    ///
+    /// The spelled-out characters are then parsed as an Id30 string, so
+    /// `is_canonical` is `false` if any of them is a confusable or an upper case
+    /// literal character.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), id30::ParseError> {
+    /// use id30::Id30Parse;
+    ///
+    /// let parse = Id30Parse::from_phonetic("Bravo Papa Victor 3 uniform quebec")?;
+    /// assert_eq!(&parse.id30.to_string(), "bpv3uq");
+    /// assert!(parse.is_canonical);
+    ///
+    /// let parse = Id30Parse::from_phonetic("bravo papa victor three uniform, oscar")?;
+    /// assert_eq!(&parse.id30.to_string(), "bpv3u0");
+    /// assert!(!parse.is_canonical);
+    /// # Ok(())}
+    /// ```
+    pub fn from_phonetic(s: &str) -> Result<Id30Parse, ParseError> {
+        let mut chars = String::with_capacity(6);
+
+        for token in s
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | '.' | ';' | ':'))
+            .filter(|token| !token.is_empty())
+        {
+            push_token(token, &mut chars)?;
+
+            if chars.len() > 6 {
+                return Err(ParseError::InvalidLength);
+            }
+        }
+
+        chars.parse()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn to_phonetic() {
+        assert_eq!(
+            Id30::try_from(0).unwrap().to_phonetic(),
+            "zero zero zero zero zero zero"
+        );
+        assert_eq!(
+            "ajx9sz".parse::<Id30>().unwrap().to_phonetic(),
+            "alfa juliett x-ray nine foxtrot zulu"
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        for i in (0..1 << 30).step_by(9_999_991) {
+            let id30 = Id30::try_from(i).unwrap();
+            assert_eq!(
+                Id30Parse::from_phonetic(&id30.to_phonetic()),
+                Ok(Id30Parse {
+                    id30,
+                    is_canonical: true
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn mixed_input() {
+        let expected: Id30 = "ajx9fz".parse().unwrap();
+
+        for input in [
+            "alfa juliett x-ray nine foxtrot zulu",
+            "ALPHA Juliet xray niner foxtrot zulu.",
+            "alpha, juliet, x-ray, 9, f, z",
+            "aj x-ray 9fz",
+            "a-j-x-9-f-z",
+        ] {
+            let parse = Id30Parse::from_phonetic(input);
+            assert_eq!(parse.map(|x| x.id30), Ok(expected), "{input}");
+        }
+    }
+
+    #[test]
+    fn non_canonical() {
+        let parse = Id30Parse::from_phonetic("oscar india lima sierra one zero").unwrap();
+        assert_eq!(parse.id30, "011f10".parse().unwrap());
+        assert!(!parse.is_canonical);
+
+        let parse = Id30Parse::from_phonetic("A B C 1 2 3").unwrap();
+        assert!(!parse.is_canonical);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Id30Parse::from_phonetic("alfa bravo charlie"),
+            Err(ParseError::InvalidLength)
+        );
+        assert_eq!(
+            Id30Parse::from_phonetic("alfa bravo charlie delta echo foxtrot golf"),
+            Err(ParseError::InvalidLength)
+        );
+        assert_eq!(
+            Id30Parse::from_phonetic("alfa bravo charlie delta echo !"),
+            Err(ParseError::InvalidCharacters)
+        );
+        assert_eq!(
+            Id30Parse::from_phonetic("alfa bravo charlie delta echo ø"),
+            Err(ParseError::InvalidCharacters)
+        );
+    }
+}
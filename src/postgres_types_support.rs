@@ -0,0 +1,135 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "postgres_types02")]
+
+use std::error::Error;
+
+use bytes1::BytesMut;
+use postgres_types02::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+
+use crate::{display::generic::with_str, Id30};
+
+/// Writes `INT4` and `INT8` as integers, and text types, such as `TEXT` and
+/// `VARCHAR`, as the canonical encoding
+impl ToSql for Id30 {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        if <i32 as ToSql>::accepts(ty) {
+            i32::from(*self).to_sql(ty, out)
+        } else if <i64 as ToSql>::accepts(ty) {
+            i64::from(*self).to_sql(ty, out)
+        } else {
+            with_str(self, |s| s.to_sql(ty, out))
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <i32 as ToSql>::accepts(ty) || <i64 as ToSql>::accepts(ty) || <&str as ToSql>::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+/// Reads `INT4` and `INT8` as integers, and text types, such as `TEXT` and
+/// `VARCHAR`, as Id30 strings, accepting non-canonical encodings
+impl<'a> FromSql<'a> for Id30 {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        if <i32 as FromSql>::accepts(ty) {
+            Ok(Id30::try_from(i32::from_sql(ty, raw)?)?)
+        } else if <i64 as FromSql>::accepts(ty) {
+            Ok(Id30::try_from(i64::from_sql(ty, raw)?)?)
+        } else {
+            Ok(<&str>::from_sql(ty, raw)?.parse()?)
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <i32 as FromSql>::accepts(ty)
+            || <i64 as FromSql>::accepts(ty)
+            || <&str as FromSql>::accepts(ty)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn to_sql(id30: Id30, ty: &Type) -> Result<Vec<u8>, Box<dyn Error + Sync + Send>> {
+        let mut out = BytesMut::new();
+        id30.to_sql_checked(ty, &mut out)?;
+        Ok(out.to_vec())
+    }
+
+    #[test]
+    fn to_sql_wire_format() {
+        let id30: Id30 = "938nkr".parse().unwrap();
+
+        assert_eq!(to_sql(id30, &Type::INT4).unwrap(), [0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(
+            to_sql(id30, &Type::INT8).unwrap(),
+            [0, 0, 0, 0, 0x12, 0x34, 0x56, 0x78]
+        );
+        assert_eq!(to_sql(id30, &Type::TEXT).unwrap(), b"938nkr");
+        assert_eq!(to_sql(id30, &Type::VARCHAR).unwrap(), b"938nkr");
+    }
+
+    #[test]
+    fn to_sql_is_canonical() {
+        let id30: Id30 = "938NKR".parse().unwrap();
+        assert_eq!(to_sql(id30, &Type::TEXT).unwrap(), b"938nkr");
+    }
+
+    #[test]
+    fn to_sql_wrong_type() {
+        let id30: Id30 = "938nkr".parse().unwrap();
+        assert!(to_sql(id30, &Type::INT2).is_err());
+        assert!(to_sql(id30, &Type::BYTEA).is_err());
+    }
+
+    #[test]
+    fn from_sql_wire_format() {
+        let id30: Id30 = "938nkr".parse().unwrap();
+
+        assert_eq!(
+            Id30::from_sql(&Type::INT4, &[0x12, 0x34, 0x56, 0x78]).unwrap(),
+            id30
+        );
+        assert_eq!(
+            Id30::from_sql(&Type::INT8, &[0, 0, 0, 0, 0x12, 0x34, 0x56, 0x78]).unwrap(),
+            id30
+        );
+        assert_eq!(Id30::from_sql(&Type::TEXT, b"938nkr").unwrap(), id30);
+        assert_eq!(Id30::from_sql(&Type::TEXT, b"938NKR").unwrap(), id30);
+    }
+
+    #[test]
+    fn from_sql_out_of_range() {
+        assert!(Id30::from_sql(&Type::INT4, &0x4000_0000i32.to_be_bytes()).is_err());
+        assert!(Id30::from_sql(&Type::INT4, &(-1i32).to_be_bytes()).is_err());
+        assert!(Id30::from_sql(&Type::INT8, &0x1_0000_0000i64.to_be_bytes()).is_err());
+        assert!(Id30::from_sql(&Type::INT8, &(-1i64).to_be_bytes()).is_err());
+        assert!(Id30::from_sql(&Type::TEXT, b"938nk").is_err());
+        assert!(Id30::from_sql(&Type::TEXT, b"938nk!").is_err());
+    }
+
+    #[test]
+    fn accepts() {
+        for ty in [Type::INT4, Type::INT8, Type::TEXT, Type::VARCHAR] {
+            assert!(<Id30 as ToSql>::accepts(&ty), "{ty}");
+            assert!(<Id30 as FromSql>::accepts(&ty), "{ty}");
+        }
+        for ty in [Type::INT2, Type::FLOAT8, Type::BYTEA] {
+            assert!(!<Id30 as ToSql>::accepts(&ty), "{ty}");
+            assert!(!<Id30 as FromSql>::accepts(&ty), "{ty}");
+        }
+    }
+}
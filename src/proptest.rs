@@ -0,0 +1,163 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "proptest1")]
+
+//! Strategies for property testing code that consumes Id30 values and
+//! strings with `proptest`.
+//!
+//! Besides valid values and canonical strings, there are strategies for valid
+//! non-canonical strings, for exercising redirect logic, and for near-miss
+//! invalid strings, for exercising error handling. `Id30` also implements
+//! proptest's [`Arbitrary`], so
+//! `any::<Id30>()` is equivalent to [`id30()`].
+//!
+//! ```
+//! # extern crate proptest1 as proptest;
+//! use id30::Id30Parse;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     # /*
+//!     #[test]
+//!     # */
+//!     fn redirects_non_canonical(s in id30::proptest::non_canonical_string()) {
+//!         let parse: Id30Parse = s.parse().unwrap();
+//!         prop_assert!(!parse.is_canonical);
+//!         prop_assert_ne!(parse.id30.to_string(), s);
+//!     }
+//! }
+//! # redirects_non_canonical();
+//! ```
+
+use std::ops::Range;
+
+use proptest1::{
+    arbitrary::Arbitrary,
+    collection::vec,
+    prelude::*,
+    sample::{select, Index},
+    strategy::Map,
+};
+
+use crate::{
+    codec_tables::{encodings, DECODE, ERR_FLAG},
+    Id30,
+};
+
+/// Any `Id30` value, uniformly distributed over the value space
+pub fn id30() -> impl Strategy<Value = Id30> {
+    Id30::arbitrary()
+}
+
+impl Arbitrary for Id30 {
+    type Parameters = ();
+    type Strategy = Map<Range<u32>, fn(u32) -> Id30>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        (0..1 << 30).prop_map(Id30)
+    }
+}
+
+/// The canonical encoding of any `Id30` value
+pub fn canonical_string() -> impl Strategy<Value = String> {
+    id30().prop_map(|id30| id30.to_string())
+}
+
+/// Valid, but non-canonical, encodings of any `Id30` value, with one or more
+/// characters replaced by an upper case or confusable alternative, such as
+/// `O` or `o` for `0` and `I`, `i`, `L` or `l` for `1`
+pub fn non_canonical_string() -> impl Strategy<Value = String> {
+    let encodings = encodings();
+    (id30(), 1..1u8 << 6, vec(any::<Index>(), 6)).prop_filter_map(
+        "Id30 without alternative encodings for the chosen characters",
+        move |(id30, mask, choices)| {
+            let mut is_canonical = true;
+            let s: String = id30
+                .to_string()
+                .bytes()
+                .zip(choices)
+                .enumerate()
+                .map(|(i, (c, choice))| {
+                    let alternatives = &encodings[(DECODE[c as usize] & 0b1_1111) as usize][1..];
+                    if mask & (1 << i) != 0 && !alternatives.is_empty() {
+                        is_canonical = false;
+                        char::from(*choice.get(alternatives))
+                    } else {
+                        char::from(c)
+                    }
+                })
+                .collect();
+            (!is_canonical).then_some(s)
+        },
+    )
+}
+
+/// Characters that are not valid in Id30 strings, including some that are
+/// easily mistaken for valid characters
+fn invalid_char() -> impl Strategy<Value = char> {
+    let ascii = (0..128u8)
+        .filter(|&c| DECODE[c as usize] == ERR_FLAG)
+        .map(char::from);
+    let unicode = ['ø', 'ö', 'ü', 'é', 'ß', 'О', 'І', '０', '\u{a0}'];
+    select(ascii.chain(unicode).collect::<Vec<_>>())
+}
+
+/// Strings that are almost, but not quite, valid Id30 strings: canonical
+/// encodings with one character replaced by an invalid character, and strings
+/// of valid characters that are too short or too long
+pub fn invalid_string() -> impl Strategy<Value = String> {
+    let bad_char = (canonical_string(), 0..6usize, invalid_char()).prop_map(|(s, i, c)| {
+        let mut chars: Vec<char> = s.chars().collect();
+        chars[i] = c;
+        chars.into_iter().collect()
+    });
+
+    let valid_chars = select(
+        (0..128u8)
+            .filter(|&c| DECODE[c as usize] != ERR_FLAG)
+            .map(char::from)
+            .collect::<Vec<_>>(),
+    );
+    let wrong_length = prop_oneof![vec(valid_chars.clone(), 0..6), vec(valid_chars, 7..13),]
+        .prop_map(|chars| chars.into_iter().collect());
+
+    prop_oneof![bad_char, wrong_length]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CanonicalId30, Id30Parse};
+
+    proptest! {
+        #[test]
+        fn id30_roundtrip(id30 in id30()) {
+            prop_assert_eq!(Id30::try_from(u32::from(id30)), Ok(id30));
+        }
+
+        #[test]
+        fn canonical(s in canonical_string()) {
+            let parse: Id30Parse = s.parse().unwrap();
+            prop_assert!(parse.is_canonical);
+            prop_assert!(s.parse::<CanonicalId30>().is_ok());
+        }
+
+        #[test]
+        fn non_canonical(s in non_canonical_string()) {
+            let parse: Id30Parse = s.parse().unwrap();
+            prop_assert!(!parse.is_canonical);
+            prop_assert_ne!(parse.id30.to_string(), s);
+        }
+
+        #[test]
+        fn invalid(s in invalid_string()) {
+            prop_assert!(s.parse::<Id30>().is_err(), "{:?}", s);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "rusqlite030")]
+
+use rusqlite030::{
+    functions::{Context, FunctionFlags},
+    types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef},
+    Connection, Error, Result,
+};
+
+use crate::{Id30, Id30Parse, ParseError};
+
+/// `Id30` is stored as an `INTEGER`
+impl ToSql for Id30 {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(i32::from(*self)))
+    }
+}
+
+impl FromSql for Id30 {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let num = i64::column_result(value)?;
+        Id30::try_from(num).map_err(|_| FromSqlError::OutOfRange(num))
+    }
+}
+
+/// Get argument `idx` as an integer that is a valid `Id30`, or `None` for
+/// `NULL`
+fn integer_arg(ctx: &Context<'_>, idx: usize) -> Result<Option<Id30>> {
+    ctx.get(idx)
+}
+
+/// Parse argument `idx` as an Id30 string, or `None` for `NULL`
+fn text_arg(ctx: &Context<'_>, idx: usize) -> Result<Option<Id30Parse>> {
+    let text = match ctx.get_raw(idx) {
+        ValueRef::Null => return Ok(None),
+        ValueRef::Text(text) => text,
+        value => return Err(Error::InvalidFunctionParameterType(idx, value.data_type())),
+    };
+
+    let parse = std::str::from_utf8(text)
+        .map_err(|_| ParseError::InvalidCharacters)
+        .and_then(str::parse)
+        .map_err(|err| Error::UserFunctionError(Box::new(err)))?;
+    Ok(Some(parse))
+}
+
+fn flags() -> FunctionFlags {
+    FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC
+}
+
+/// Register SQL functions for working with Id30 values on a SQLite connection:
+///
+///  - `id30_encode(integer)` gives the canonical Id30 string for an integer
+///  - `id30_decode(text)` gives the integer value of an Id30 string, which may
+///    be non-canonical
+///  - `id30_is_canonical(text)` gives `1` if the Id30 string is in the
+///    canonical encoding and `0` if it is not
+///  - `id30_canonicalize(text)` gives the canonical encoding of an Id30 string
+///
+/// The functions are deterministic, so they can be used in indexes and
+/// generated columns. They give `NULL` for `NULL` input, and fail the
+/// statement for integers that are out of range for `Id30` and strings that
+/// are not valid Id30 strings.
+///
+/// ```
+/// # use rusqlite030 as rusqlite;
+/// # fn main() -> rusqlite::Result<()> {
+/// let conn = rusqlite::Connection::open_in_memory()?;
+/// id30::register_sqlite_functions(&conn)?;
+///
+/// let id: String = conn.query_row("SELECT id30_encode(0x12345678)", [], |row| row.get(0))?;
+/// assert_eq!(id, "938nkr");
+/// # Ok(())
+/// # }
+/// ```
+pub fn register_sqlite_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function("id30_encode", 1, flags(), |ctx| {
+        Ok(integer_arg(ctx, 0)?.map(|id30| id30.to_string()))
+    })?;
+
+    conn.create_scalar_function("id30_decode", 1, flags(), |ctx| {
+        Ok(text_arg(ctx, 0)?.map(|parse| parse.id30))
+    })?;
+
+    conn.create_scalar_function("id30_is_canonical", 1, flags(), |ctx| {
+        Ok(text_arg(ctx, 0)?.map(|parse| parse.is_canonical))
+    })?;
+
+    conn.create_scalar_function("id30_canonicalize", 1, flags(), |ctx| {
+        Ok(text_arg(ctx, 0)?.map(|parse| parse.id30.to_string()))
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        register_sqlite_functions(&conn).unwrap();
+        conn
+    }
+
+    fn query<T: FromSql>(conn: &Connection, sql: &str) -> Result<T> {
+        conn.query_row(sql, [], |row| row.get(0))
+    }
+
+    #[test]
+    fn to_sql() -> Result<()> {
+        let conn = conn();
+        let id30 = Id30::try_from(0x1234_5678).unwrap();
+
+        let int: i64 = conn.query_row("SELECT ?", [id30], |row| row.get(0))?;
+        assert_eq!(int, 0x1234_5678);
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_sql() -> Result<()> {
+        let conn = conn();
+
+        let id30: Id30 = query(&conn, "SELECT 0x12345678")?;
+        assert_eq!(id30, Id30::try_from(0x1234_5678).unwrap());
+
+        assert!(query::<Id30>(&conn, "SELECT 0x40000000").is_err());
+        assert!(query::<Id30>(&conn, "SELECT -1").is_err());
+        assert!(query::<Id30>(&conn, "SELECT 'bpv3uq'").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip() -> Result<()> {
+        let conn = conn();
+        conn.execute("CREATE TABLE posts (id INTEGER PRIMARY KEY NOT NULL)", [])?;
+
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        conn.execute("INSERT INTO posts (id) VALUES (?)", [id30])?;
+
+        let stored: Id30 = conn.query_row("SELECT id FROM posts WHERE id = ?", [id30], |row| {
+            row.get(0)
+        })?;
+        assert_eq!(stored, id30);
+
+        let encoded: String = query(&conn, "SELECT id30_encode(id) FROM posts")?;
+        assert_eq!(encoded, "bpv3uq");
+
+        let found: Id30 = query(
+            &conn,
+            "SELECT id FROM posts WHERE id = id30_decode('BPV3UQ')",
+        )?;
+        assert_eq!(found, id30);
+
+        Ok(())
+    }
+
+    #[test]
+    fn functions() -> Result<()> {
+        let conn = conn();
+
+        assert_eq!(query::<String>(&conn, "SELECT id30_encode(0)")?, "000000");
+        assert_eq!(
+            query::<i64>(&conn, "SELECT id30_decode('bpv3uq')")?,
+            0x176d8f57
+        );
+        assert_eq!(
+            query::<i64>(&conn, "SELECT id30_decode('BPV3UQ')")?,
+            0x176d8f57
+        );
+        assert!(query::<bool>(&conn, "SELECT id30_is_canonical('bpv3uq')")?);
+        assert!(!query::<bool>(&conn, "SELECT id30_is_canonical('BPV3UQ')")?);
+        assert_eq!(
+            query::<String>(&conn, "SELECT id30_canonicalize('BPV3UQ')")?,
+            "bpv3uq"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn functions_null() -> Result<()> {
+        let conn = conn();
+
+        for function in [
+            "id30_encode",
+            "id30_decode",
+            "id30_is_canonical",
+            "id30_canonicalize",
+        ] {
+            let res: Option<i64> = query(&conn, &format!("SELECT {function}(NULL)"))?;
+            assert_eq!(res, None, "{function}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn functions_invalid() {
+        let conn = conn();
+
+        for sql in [
+            "SELECT id30_encode(0x40000000)",
+            "SELECT id30_encode(-1)",
+            "SELECT id30_encode('bpv3uq')",
+            "SELECT id30_decode('bpv3u')",
+            "SELECT id30_decode('bpv3u!')",
+            "SELECT id30_decode(1)",
+            "SELECT id30_is_canonical('bpv3u')",
+            "SELECT id30_canonicalize('bpv3u')",
+        ] {
+            assert!(query::<Option<String>>(&conn, sql).is_err(), "{sql}");
+        }
+    }
+
+    #[test]
+    fn functions_are_deterministic() -> Result<()> {
+        let conn = conn();
+
+        conn.execute_batch(
+            "CREATE TABLE posts (id INTEGER PRIMARY KEY NOT NULL);
+            CREATE INDEX posts_id30 ON posts (id30_encode(id));",
+        )?;
+
+        Ok(())
+    }
+}
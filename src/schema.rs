@@ -0,0 +1,40 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(any(feature = "schemars1", feature = "utoipa5"))]
+
+//! Definitions shared by the schema integrations
+
+/// A regular expression pattern matching an entire Id30 string in any valid
+/// encoding
+pub(crate) const PATTERN: &str = "^[0-9A-Za-z]{6}$";
+
+/// A regular expression pattern matching an entire canonical Id30 string
+pub(crate) const CANONICAL_PATTERN: &str = "^[0-9a-hjkmnp-rt-z]{6}$";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::codec_tables::{ALT_FLAG, DECODE, ERR_FLAG};
+
+    #[test]
+    fn patterns_match_decode_table() {
+        let pattern = regex1::Regex::new(PATTERN).unwrap();
+        let canonical_pattern = regex1::Regex::new(CANONICAL_PATTERN).unwrap();
+
+        for c in 0..=127u8 {
+            let s = String::from_utf8(vec![c; 6]).unwrap();
+            let decoded = DECODE[c as usize];
+            assert_eq!(pattern.is_match(&s), decoded & ERR_FLAG == 0, "{s}");
+            assert_eq!(canonical_pattern.is_match(&s), decoded < ALT_FLAG, "{s}");
+        }
+
+        assert!(!pattern.is_match("bpv3u"));
+        assert!(!pattern.is_match("bpv3uqq"));
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "schemars1")]
+
+use std::borrow::Cow;
+
+use schemars1::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::{
+    schema::{CANONICAL_PATTERN, PATTERN},
+    CanonicalId30, Id30, Id30Parse,
+};
+
+fn string_schema(pattern: &str) -> Schema {
+    json_schema!({
+        "type": "string",
+        "minLength": 6,
+        "maxLength": 6,
+        "pattern": pattern,
+    })
+}
+
+/// `Id30` is described as a string in any valid encoding, matching how it is
+/// deserialized from human readable formats
+impl JsonSchema for Id30 {
+    fn schema_name() -> Cow<'static, str> {
+        "Id30".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "id30::Id30".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        string_schema(PATTERN)
+    }
+}
+
+/// `CanonicalId30` is described as a string in the canonical encoding. Use
+/// `#[schemars(with = "id30::CanonicalId30")]` for `Id30` fields that are
+/// deserialized with `id30::serde::canonical`.
+impl JsonSchema for CanonicalId30 {
+    fn schema_name() -> Cow<'static, str> {
+        "CanonicalId30".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "id30::CanonicalId30".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        string_schema(CANONICAL_PATTERN)
+    }
+}
+
+/// `Id30Parse` is described as a string in any valid encoding for
+/// deserialization, and as a struct with the fields `id30` and `is_canonical`
+/// for serialization
+impl JsonSchema for Id30Parse {
+    fn schema_name() -> Cow<'static, str> {
+        "Id30Parse".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "id30::Id30Parse".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        if generator.contract().is_deserialize() {
+            return string_schema(PATTERN);
+        }
+
+        json_schema!({
+            "type": "object",
+            "properties": {
+                "id30": generator.subschema_for::<Id30>(),
+                "is_canonical": generator.subschema_for::<bool>(),
+            },
+            "required": ["id30", "is_canonical"],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use schemars1::generate::SchemaSettings;
+
+    use super::*;
+
+    #[test]
+    fn id30() {
+        assert_eq!(
+            Id30::json_schema(&mut SchemaGenerator::default()),
+            json_schema!({
+                "type": "string",
+                "minLength": 6,
+                "maxLength": 6,
+                "pattern": "^[0-9A-Za-z]{6}$",
+            })
+        );
+    }
+
+    #[test]
+    fn canonical_id30() {
+        assert_eq!(
+            CanonicalId30::json_schema(&mut SchemaGenerator::default()),
+            json_schema!({
+                "type": "string",
+                "minLength": 6,
+                "maxLength": 6,
+                "pattern": "^[0-9a-hjkmnp-rt-z]{6}$",
+            })
+        );
+    }
+
+    #[test]
+    fn id30parse() {
+        let mut generator = SchemaSettings::default().for_deserialize().into_generator();
+        assert_eq!(
+            Id30Parse::json_schema(&mut generator),
+            Id30::json_schema(&mut generator)
+        );
+
+        let mut generator = SchemaSettings::default().for_serialize().into_generator();
+        assert_eq!(
+            Id30Parse::json_schema(&mut generator),
+            json_schema!({
+                "type": "object",
+                "properties": {
+                    "id30": { "$ref": "#/$defs/Id30" },
+                    "is_canonical": { "type": "boolean" },
+                },
+                "required": ["id30", "is_canonical"],
+            })
+        );
+    }
+
+    #[test]
+    fn derive() {
+        #[derive(JsonSchema)]
+        #[schemars(crate = "schemars1")]
+        #[allow(unused)]
+        struct Post {
+            id: Id30,
+            #[schemars(with = "CanonicalId30")]
+            parent: Id30,
+        }
+
+        let schema = SchemaGenerator::default().into_root_schema_for::<Post>();
+        let reference = |field: &str| {
+            let pointer = format!("/properties/{field}/$ref");
+            schema.as_value().pointer(&pointer)?.as_str()
+        };
+        assert_eq!(reference("id"), Some("#/$defs/Id30"));
+        assert_eq!(reference("parent"), Some("#/$defs/CanonicalId30"));
+    }
+}
@@ -0,0 +1,293 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "serde1")]
+
+//! Adapters for serializing [`Id30`][crate::Id30] values in alternative forms
+//! with serde, for use with the `#[serde(with = "...")]` field attribute.
+//!
+//! By default, `Id30` is serialized as an Id30 string in human readable
+//! formats, such as JSON, and as `u32` in other formats, such as bincode. Use
+//! [`as_str`] or [`as_u32`] to pick one form for all formats, or
+//! [`canonical`] to reject non-canonical Id30 strings. [`words`] and
+//! [`crockford`] use the three-word encoding and Crockford's base 32 encoding
+//! instead of Id30 strings.
+//!
+//! ```
+//! # use serde1::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! # #[serde(crate = "serde1")]
+//! struct Invitation {
+//!     #[serde(with = "id30::serde::words")]
+//!     code: id30::Id30,
+//! }
+//! ```
+
+/// Serialize and deserialize an [`Id30`][crate::Id30] as an Id30 string in
+/// all formats, rather than as `u32` in formats that are not human readable.
+///
+/// Deserialization accepts non-canonical input.
+pub mod as_str {
+    use serde1::{Deserializer, Serializer};
+
+    use crate::{display::generic::with_str, serde_support::Id30Visitor, Id30};
+
+    #[allow(missing_docs)]
+    pub fn serialize<S>(id30: &Id30, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        with_str(id30, move |s| serializer.serialize_str(s))
+    }
+
+    #[allow(missing_docs)]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Id30, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(Id30Visitor)
+    }
+}
+
+/// Serialize and deserialize an [`Id30`][crate::Id30] as `u32` in all
+/// formats, rather than as an Id30 string in formats that are human readable.
+///
+/// Deserialization accepts any integer in the range of `Id30`.
+pub mod as_u32 {
+    use serde1::{Deserializer, Serializer};
+
+    use crate::{serde_support::Id30Visitor, Id30};
+
+    #[allow(missing_docs)]
+    pub fn serialize<S>(id30: &Id30, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u32(u32::from(*id30))
+    }
+
+    #[allow(missing_docs)]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Id30, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_u32(Id30Visitor)
+    }
+}
+
+/// Serialize and deserialize an [`Id30`][crate::Id30] like `Id30` itself,
+/// but reject non-canonical Id30 strings when deserializing, as implemented by
+/// [`CanonicalId30`][crate::CanonicalId30].
+///
+/// This is useful for internal APIs, where a non-canonical ID indicates a bug
+/// in the client. The error includes the canonical encoding of the ID.
+pub mod canonical {
+    use serde1::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::{CanonicalId30, Id30};
+
+    #[allow(missing_docs)]
+    pub fn serialize<S>(id30: &Id30, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        id30.serialize(serializer)
+    }
+
+    #[allow(missing_docs)]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Id30, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        CanonicalId30::deserialize(deserializer).map(Id30::from)
+    }
+}
+
+/// Serialize and deserialize an [`Id30`][crate::Id30] as three words, as
+/// implemented by [`Id30::to_words`][crate::Id30::to_words] and
+/// [`Id30::from_words`][crate::Id30::from_words].
+///
+/// Deserialization accepts non-canonical input, see
+/// [`Id30Parse::from_words`][crate::Id30Parse::from_words].
+pub mod words {
+    use serde1::{de, Deserializer, Serializer};
+
+    use crate::Id30;
+
+    struct WordsVisitor;
+
+    impl de::Visitor<'_> for WordsVisitor {
+        type Value = Id30;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a valid Id30 word encoding")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Id30::from_words(v).map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+    }
+
+    #[allow(missing_docs)]
+    pub fn serialize<S>(id30: &Id30, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&id30.to_words())
+    }
+
+    #[allow(missing_docs)]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Id30, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(WordsVisitor)
+    }
+}
+
+/// Serialize and deserialize an [`Id30`][crate::Id30] in Crockford's base 32
+/// encoding, as implemented by [`Id30::to_crockford`][crate::Id30::to_crockford]
+/// and [`Id30::from_crockford`][crate::Id30::from_crockford].
+///
+/// Deserialization accepts non-canonical input, see
+/// [`Id30Parse::from_crockford`][crate::Id30Parse::from_crockford]. See also
+/// the [`Crockford`][crate::Crockford] adapter type.
+pub mod crockford {
+    use serde1::{de, Deserializer, Serializer};
+
+    use crate::{Crockford, Id30};
+
+    struct CrockfordVisitor;
+
+    impl de::Visitor<'_> for CrockfordVisitor {
+        type Value = Id30;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a valid Crockford base 32 string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            Id30::from_crockford(v).map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+    }
+
+    #[allow(missing_docs)]
+    pub fn serialize<S>(id30: &Id30, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serde1::Serialize::serialize(&Crockford(*id30), serializer)
+    }
+
+    #[allow(missing_docs)]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Id30, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(CrockfordVisitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde1::{Deserialize, Serialize};
+    use serde_test1::{
+        assert_de_tokens, assert_de_tokens_error, assert_tokens, Configure, Readable, Token,
+    };
+
+    use crate::Id30;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde1", transparent)]
+    struct AsStr(#[serde(with = "super::as_str")] Id30);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde1", transparent)]
+    struct AsU32(#[serde(with = "super::as_u32")] Id30);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde1", transparent)]
+    struct Canonical(#[serde(with = "super::canonical")] Id30);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde1", transparent)]
+    struct Words(#[serde(with = "super::words")] Id30);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(crate = "serde1")]
+    struct Crockford {
+        #[serde(with = "super::crockford")]
+        id30: Id30,
+    }
+
+    #[test]
+    fn as_str() {
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        assert_tokens(&AsStr(id30).readable(), &[Token::Str("bpv3uq")]);
+        assert_tokens(&AsStr(id30).compact(), &[Token::Str("bpv3uq")]);
+        assert_de_tokens(&AsStr(id30), &[Token::Str("BPV3UQ")]);
+    }
+
+    #[test]
+    fn as_u32() {
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        assert_tokens(&AsU32(id30).readable(), &[Token::U32(0x176d_8f57)]);
+        assert_tokens(&AsU32(id30).compact(), &[Token::U32(0x176d_8f57)]);
+        assert_de_tokens(&AsU32(id30), &[Token::U64(0x176d_8f57)]);
+        assert_de_tokens_error::<AsU32>(
+            &[Token::U32(0x4000_0000)],
+            "invalid value: integer `1073741824`, expected a valid Id30 string or integer",
+        );
+    }
+
+    #[test]
+    fn canonical() {
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        assert_tokens(&Canonical(id30).readable(), &[Token::Str("bpv3uq")]);
+        assert_tokens(&Canonical(id30).compact(), &[Token::U32(0x176d_8f57)]);
+        assert_de_tokens_error::<Readable<Canonical>>(
+            &[Token::Str("bpv3Uq")],
+            "non-canonical encoding of Id30, the canonical encoding is bpv3uq, not bpv3Uq",
+        );
+    }
+
+    #[test]
+    fn words() {
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        assert_tokens(&Words(id30), &[Token::Str("flee-square-speak")]);
+        assert_de_tokens(&Words(id30), &[Token::Str("Flee square spaek")]);
+        assert_de_tokens_error::<Words>(
+            &[Token::Str("bpv3uq")],
+            "invalid value: string \"bpv3uq\", expected a valid Id30 word encoding",
+        );
+    }
+
+    #[test]
+    fn crockford() {
+        // The codec itself is tested with the `Crockford` type
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        assert_tokens(
+            &Crockford { id30 },
+            &[
+                Token::Struct {
+                    name: "Crockford",
+                    len: 1,
+                },
+                Token::Str("id30"),
+                Token::Str("BPV3TQ"),
+                Token::StructEnd,
+            ],
+        );
+    }
+}
@@ -8,17 +8,24 @@
 
 #![cfg(feature = "serde1")]
 
-use serde1::{de, Deserialize, Serialize};
+use serde1::{
+    de, ser,
+    ser::{SerializeSeq, SerializeStruct},
+    Deserialize, Serialize,
+};
 
-use crate::{display::generic::with_str, Id30, Id30Parse};
+use crate::{
+    display::generic::with_str, CanonicalId30, CanonicalParseError, Id30, Id30Parse, Id30Set,
+};
 
-struct Id30Visitor;
+/// Accepts both Id30 strings and integers in the range of `Id30`
+pub(crate) struct Id30Visitor;
 
 impl<'de> de::Visitor<'de> for Id30Visitor {
     type Value = Id30;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("a valid Id30 string")
+        formatter.write_str("a valid Id30 string or integer")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
@@ -28,24 +35,93 @@ impl<'de> de::Visitor<'de> for Id30Visitor {
         v.parse()
             .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
     }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        std::str::from_utf8(v)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Id30::try_from(v).map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Id30::try_from(v).map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
 }
 
+/// `Id30` is deserialized from either strings or integers in human readable
+/// formats, and from `u32` otherwise.
 impl<'de> Deserialize<'de> for Id30 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde1::Deserializer<'de>,
     {
-        deserializer.deserialize_str(Id30Visitor)
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(Id30Visitor)
+        } else {
+            deserializer.deserialize_u32(Id30Visitor)
+        }
     }
 }
 
+const ID30_PARSE_FIELDS: &[&str] = &["id30", "is_canonical"];
+
+enum Id30ParseField {
+    Id30,
+    IsCanonical,
+}
+
+struct Id30ParseFieldVisitor;
+
+impl de::Visitor<'_> for Id30ParseFieldVisitor {
+    type Value = Id30ParseField;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("`id30` or `is_canonical`")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match v {
+            "id30" => Ok(Id30ParseField::Id30),
+            "is_canonical" => Ok(Id30ParseField::IsCanonical),
+            _ => Err(E::unknown_field(v, ID30_PARSE_FIELDS)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Id30ParseField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde1::Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(Id30ParseFieldVisitor)
+    }
+}
+
+/// Accepts Id30 strings, for which `is_canonical` is determined by parsing,
+/// and `Id30Parse` structs, as serialized by `Id30Parse`
 struct Id30ParseVisitor;
 
 impl<'de> de::Visitor<'de> for Id30ParseVisitor {
     type Value = Id30Parse;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        formatter.write_str("a valid Id30 string")
+        formatter.write_str("a valid Id30 string or an Id30Parse struct")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
@@ -55,47 +131,312 @@ impl<'de> de::Visitor<'de> for Id30ParseVisitor {
         v.parse()
             .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
     }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        std::str::from_utf8(v)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let id30 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let is_canonical = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(Id30Parse { id30, is_canonical })
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut id30 = None;
+        let mut is_canonical = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                Id30ParseField::Id30 if id30.is_some() => {
+                    return Err(de::Error::duplicate_field("id30"))
+                }
+                Id30ParseField::Id30 => id30 = Some(map.next_value()?),
+                Id30ParseField::IsCanonical if is_canonical.is_some() => {
+                    return Err(de::Error::duplicate_field("is_canonical"))
+                }
+                Id30ParseField::IsCanonical => is_canonical = Some(map.next_value()?),
+            }
+        }
+        Ok(Id30Parse {
+            id30: id30.ok_or_else(|| de::Error::missing_field("id30"))?,
+            is_canonical: is_canonical.ok_or_else(|| de::Error::missing_field("is_canonical"))?,
+        })
+    }
 }
 
+/// `Id30Parse` is deserialized from either an Id30 string or a struct, as
+/// serialized by `Id30Parse`, in human readable formats, and from a struct
+/// otherwise.
 impl<'de> Deserialize<'de> for Id30Parse {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde1::Deserializer<'de>,
     {
-        deserializer.deserialize_str(Id30ParseVisitor)
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(Id30ParseVisitor)
+        } else {
+            deserializer.deserialize_struct("Id30Parse", ID30_PARSE_FIELDS, Id30ParseVisitor)
+        }
+    }
+}
+
+/// `Id30Parse` is serialized as a struct with the fields `id30` and
+/// `is_canonical`, to keep the canonicality flag. The `id30` field is
+/// serialized like `Id30`.
+impl Serialize for Id30Parse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde1::Serializer,
+    {
+        let mut state = serializer.serialize_struct("Id30Parse", 2)?;
+        state.serialize_field("id30", &self.id30)?;
+        state.serialize_field("is_canonical", &self.is_canonical)?;
+        state.end()
+    }
+}
+
+/// Accepts canonical Id30 strings and integers in the range of `Id30`
+struct CanonicalId30Visitor;
+
+impl<'de> de::Visitor<'de> for CanonicalId30Visitor {
+    type Value = CanonicalId30;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a canonical Id30 string or integer")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        v.parse().map_err(|err| match err {
+            CanonicalParseError::Invalid(_) => E::invalid_value(de::Unexpected::Str(v), &self),
+            CanonicalParseError::NonCanonical(_) => E::custom(format_args!("{err}, not {v}")),
+        })
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match std::str::from_utf8(v) {
+            Ok(s) => self.visit_str(s),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Id30Visitor.visit_u64(v).map(CanonicalId30)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Id30Visitor.visit_i64(v).map(CanonicalId30)
+    }
+}
+
+/// `CanonicalId30` is deserialized like `Id30`, but rejects non-canonical
+/// Id30 strings with an error that includes the canonical encoding.
+impl<'de> Deserialize<'de> for CanonicalId30 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde1::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(CanonicalId30Visitor)
+        } else {
+            deserializer.deserialize_u32(CanonicalId30Visitor)
+        }
+    }
+}
+
+impl Serialize for CanonicalId30 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde1::Serializer,
+    {
+        self.0.serialize(serializer)
     }
 }
 
+/// `Id30` is serialized as a string in human readable formats, and as `u32`
+/// otherwise.
 impl Serialize for Id30 {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde1::Serializer,
     {
-        with_str(self, move |id30_str| serializer.serialize_str(id30_str))
+        if serializer.is_human_readable() {
+            with_str(self, move |id30_str| serializer.serialize_str(id30_str))
+        } else {
+            serializer.serialize_u32(self.0)
+        }
+    }
+}
+
+/// `Id30Set` is serialized as a sequence of `Id30` strings in human readable
+/// formats, and as bytes in the portable Roaring format otherwise.
+impl Serialize for Id30Set {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde1::Serializer,
+    {
+        if serializer.is_human_readable() {
+            let mut seq = serializer.serialize_seq(Some(self.len()))?;
+            for id30 in self {
+                seq.serialize_element(&id30)?;
+            }
+            seq.end()
+        } else {
+            let mut buf = Vec::with_capacity(self.serialized_size());
+            self.serialize_into(&mut buf).map_err(ser::Error::custom)?;
+            serializer.serialize_bytes(&buf)
+        }
+    }
+}
+
+struct Id30SetVisitor;
+
+impl<'de> de::Visitor<'de> for Id30SetVisitor {
+    type Value = Id30Set;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a sequence of Id30 values or a serialized Id30Set")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let mut set = Id30Set::new();
+        while let Some(id30) = seq.next_element()? {
+            set.insert(id30);
+        }
+        Ok(set)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Id30Set::deserialize_from(v).map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Id30Set {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde1::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_seq(Id30SetVisitor)
+        } else {
+            deserializer.deserialize_bytes(Id30SetVisitor)
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
-    use serde_test1::{assert_de_tokens, assert_tokens, Token};
+    use serde_test1::{
+        assert_de_tokens, assert_de_tokens_error, assert_tokens, Compact, Configure, Readable,
+        Token,
+    };
+
+    use crate::{CanonicalId30, Id30, Id30Parse, Id30Set};
 
-    use crate::{Id30, Id30Parse};
+    // serde_test panics on `is_human_readable` unless the test case is marked
+    // with `Configure`, so the readable cases need `.readable()` as well
 
     #[test]
     fn deserialize_id30() {
         assert_tokens(
-            &Id30::try_from(0x3fff_ffff).unwrap(),
+            &Id30::try_from(0x3fff_ffff).unwrap().readable(),
             &[Token::Str("zzzzzz")],
         )
     }
 
+    #[test]
+    fn serialize_id30_compact() {
+        assert_tokens(
+            &Id30::try_from(0x3fff_ffff).unwrap().compact(),
+            &[Token::U32(0x3fff_ffff)],
+        )
+    }
+
+    #[test]
+    fn deserialize_id30_integer() {
+        let id30 = Id30::try_from(0x1234_5678).unwrap();
+        assert_de_tokens(&id30.readable(), &[Token::U64(0x1234_5678)]);
+        assert_de_tokens(&id30.readable(), &[Token::I64(0x1234_5678)]);
+        assert_de_tokens(&id30.readable(), &[Token::U32(0x1234_5678)]);
+        assert_de_tokens(&id30.compact(), &[Token::U64(0x1234_5678)]);
+        assert_de_tokens(&id30.compact(), &[Token::Str("938nkr")]);
+
+        assert_de_tokens_error::<Readable<Id30>>(
+            &[Token::U64(0x4000_0000)],
+            "invalid value: integer `1073741824`, expected a valid Id30 string or integer",
+        );
+        assert_de_tokens_error::<Readable<Id30>>(
+            &[Token::I64(-1)],
+            "invalid value: integer `-1`, expected a valid Id30 string or integer",
+        );
+        assert_de_tokens_error::<Readable<Id30>>(
+            &[Token::Bool(true)],
+            "invalid type: boolean `true`, expected a valid Id30 string or integer",
+        );
+    }
+
+    #[test]
+    fn canonical_id30() {
+        let id30 = CanonicalId30("bpv3uq".parse().unwrap());
+        assert_tokens(&id30.readable(), &[Token::Str("bpv3uq")]);
+        assert_tokens(&id30.compact(), &[Token::U32(0x176d_8f57)]);
+        assert_de_tokens(&id30.readable(), &[Token::U64(0x176d_8f57)]);
+
+        assert_de_tokens_error::<Readable<CanonicalId30>>(
+            &[Token::Str("BPV3UQ")],
+            "non-canonical encoding of Id30, the canonical encoding is bpv3uq, not BPV3UQ",
+        );
+        assert_de_tokens_error::<Readable<CanonicalId30>>(
+            &[Token::Str("bpv3u")],
+            "invalid value: string \"bpv3u\", expected a canonical Id30 string or integer",
+        );
+        assert_de_tokens_error::<Readable<CanonicalId30>>(
+            &[Token::U64(0x4000_0000)],
+            "invalid value: integer `1073741824`, expected a valid Id30 string or integer",
+        );
+    }
+
     #[test]
     fn deserialize_id30parse() {
         assert_de_tokens(
             &Id30Parse {
                 id30: Id30::try_from(0x3fff_ffff).unwrap(),
                 is_canonical: true,
-            },
+            }
+            .readable(),
             &[Token::Str("zzzzzz")],
         );
 
@@ -103,8 +444,184 @@ mod test {
             &Id30Parse {
                 id30: Id30::try_from(0x3fff_ffff).unwrap(),
                 is_canonical: false,
-            },
+            }
+            .readable(),
             &[Token::Str("zzzZZZ")],
         );
     }
+
+    #[test]
+    fn deserialize_id30_bytes() {
+        let id30 = Id30::try_from(0x3fff_ffff).unwrap();
+        assert_de_tokens(&id30.readable(), &[Token::Bytes(b"zzzzzz")]);
+        assert_de_tokens(&id30.readable(), &[Token::BorrowedBytes(b"zzzZZZ")]);
+        assert_de_tokens(&id30.readable(), &[Token::ByteBuf(b"zzzzzz")]);
+        assert_de_tokens(&id30.compact(), &[Token::BorrowedBytes(b"zzzzzz")]);
+        assert_de_tokens(&CanonicalId30(id30).readable(), &[Token::Bytes(b"zzzzzz")]);
+
+        assert_de_tokens_error::<Readable<Id30>>(
+            &[Token::Bytes(b"zzzzz\xff")],
+            "invalid value: byte array, expected a valid Id30 string or integer",
+        );
+        assert_de_tokens_error::<Readable<CanonicalId30>>(
+            &[Token::Bytes(b"zzzZZZ")],
+            "non-canonical encoding of Id30, the canonical encoding is zzzzzz, not zzzZZZ",
+        );
+    }
+
+    #[test]
+    fn deserialize_id30parse_bytes() {
+        let parse = |is_canonical| Id30Parse {
+            id30: Id30::try_from(0x3fff_ffff).unwrap(),
+            is_canonical,
+        };
+        assert_de_tokens(&parse(true).readable(), &[Token::Bytes(b"zzzzzz")]);
+        assert_de_tokens(&parse(false).readable(), &[Token::BorrowedBytes(b"zzzZZZ")]);
+        assert_de_tokens(&parse(false).readable(), &[Token::ByteBuf(b"zzzZZZ")]);
+
+        assert_de_tokens_error::<Readable<Id30Parse>>(
+            &[Token::Bytes(b"zzzzz")],
+            "invalid value: byte array, expected a valid Id30 string or an Id30Parse struct",
+        );
+    }
+
+    #[test]
+    fn serialize_id30parse() {
+        let parse = Id30Parse {
+            id30: Id30::try_from(0x3fff_ffff).unwrap(),
+            is_canonical: false,
+        };
+
+        assert_tokens(
+            &parse.readable(),
+            &[
+                Token::Struct {
+                    name: "Id30Parse",
+                    len: 2,
+                },
+                Token::Str("id30"),
+                Token::Str("zzzzzz"),
+                Token::Str("is_canonical"),
+                Token::Bool(false),
+                Token::StructEnd,
+            ],
+        );
+
+        let parse = Id30Parse {
+            id30: Id30::try_from(0x3fff_ffff).unwrap(),
+            is_canonical: true,
+        };
+
+        assert_tokens(
+            &parse.compact(),
+            &[
+                Token::Struct {
+                    name: "Id30Parse",
+                    len: 2,
+                },
+                Token::Str("id30"),
+                Token::U32(0x3fff_ffff),
+                Token::Str("is_canonical"),
+                Token::Bool(true),
+                Token::StructEnd,
+            ],
+        );
+    }
+
+    #[test]
+    fn deserialize_id30parse_struct() {
+        let parse = |is_canonical| Id30Parse {
+            id30: Id30::try_from(0x3fff_ffff).unwrap(),
+            is_canonical,
+        };
+
+        assert_de_tokens(
+            &parse(false).readable(),
+            &[
+                Token::Map { len: Some(2) },
+                Token::Str("is_canonical"),
+                Token::Bool(false),
+                Token::Str("id30"),
+                Token::Str("zzzzzz"),
+                Token::MapEnd,
+            ],
+        );
+
+        assert_de_tokens(
+            &parse(true).compact(),
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U32(0x3fff_ffff),
+                Token::Bool(true),
+                Token::SeqEnd,
+            ],
+        );
+
+        assert_de_tokens(
+            &parse(true).compact(),
+            &[
+                Token::Tuple { len: 2 },
+                Token::U32(0x3fff_ffff),
+                Token::Bool(true),
+                Token::TupleEnd,
+            ],
+        );
+
+        assert_de_tokens_error::<Readable<Id30Parse>>(
+            &[
+                Token::Map { len: Some(1) },
+                Token::Str("id30"),
+                Token::Str("zzzzzz"),
+                Token::MapEnd,
+            ],
+            "missing field `is_canonical`",
+        );
+
+        assert_de_tokens_error::<Readable<Id30Parse>>(
+            &[
+                Token::Map { len: Some(2) },
+                Token::Str("id30"),
+                Token::Str("zzzzzz"),
+                Token::Str("id30"),
+            ],
+            "duplicate field `id30`",
+        );
+
+        assert_de_tokens_error::<Readable<Id30Parse>>(
+            &[Token::Map { len: Some(1) }, Token::Str("id")],
+            "unknown field `id`, expected `id30` or `is_canonical`",
+        );
+
+        assert_de_tokens_error::<Compact<Id30Parse>>(
+            &[
+                Token::Seq { len: Some(1) },
+                Token::U32(0x3fff_ffff),
+                Token::SeqEnd,
+            ],
+            "invalid length 1, expected a valid Id30 string or an Id30Parse struct",
+        );
+    }
+
+    #[test]
+    fn serialize_id30set() {
+        let set = Id30Set::from([
+            Id30::try_from(0x1234_5678).unwrap(),
+            Id30::try_from(0x3fff_ffff).unwrap(),
+        ]);
+
+        assert_tokens(
+            &set.clone().readable(),
+            &[
+                Token::Seq { len: Some(2) },
+                Token::Str("938nkr"),
+                Token::Str("zzzzzz"),
+                Token::SeqEnd,
+            ],
+        );
+
+        let mut buf = vec![];
+        set.serialize_into(&mut buf).unwrap();
+        let buf: &'static [u8] = buf.leak();
+        assert_tokens(&set.compact(), &[Token::Bytes(buf)]);
+    }
 }
@@ -0,0 +1,449 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::{fmt, fmt::Write, str::FromStr};
+
+use crate::codec_tables::{encodings, ENCODE};
+
+/// The exclusive upper bound of Id30 values, `2^30`
+const LIMIT: u32 = 1 << 30;
+
+/// A SQL dialect for generating SQL that works with Id30 values in the
+/// database, for example for showing Id30 strings in ad-hoc queries and views.
+///
+/// The generated SQL is derived from the same tables as the Rust
+/// implementation, so encoding and decoding in the database agrees with
+/// [`Id30`](crate::Id30), including the handling of upper case and confusable
+/// characters.
+///
+/// ```
+/// # use id30::SqlDialect;
+/// let schema = SqlDialect::Postgres.schema();
+/// assert!(schema.contains("CREATE DOMAIN id30 AS integer"));
+/// assert!(schema.contains("FUNCTION id30_encode"));
+/// assert!(schema.contains("FUNCTION id30_decode"));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SqlDialect {
+    /// PostgreSQL
+    Postgres,
+
+    /// MySQL, version 8.0.16 or later for the `CHECK` constraint to be
+    /// enforced
+    MySql,
+
+    /// SQLite
+    Sqlite,
+}
+
+/// The given string was not the name of a supported SQL dialect
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UnknownDialectError;
+
+impl fmt::Display for UnknownDialectError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "unknown SQL dialect, expected one of postgres, mysql or sqlite"
+        )
+    }
+}
+
+impl std::error::Error for UnknownDialectError {}
+
+/// Parses `postgres`, `mysql` or `sqlite`, case-insensitively. `postgresql`
+/// and `pg` are accepted for Postgres.
+impl FromStr for SqlDialect {
+    type Err = UnknownDialectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "postgres" | "postgresql" | "pg" => Ok(SqlDialect::Postgres),
+            "mysql" => Ok(SqlDialect::MySql),
+            "sqlite" => Ok(SqlDialect::Sqlite),
+            _ => Err(UnknownDialectError),
+        }
+    }
+}
+
+impl fmt::Display for SqlDialect {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str(match self {
+            SqlDialect::Postgres => "postgres",
+            SqlDialect::MySql => "mysql",
+            SqlDialect::Sqlite => "sqlite",
+        })
+    }
+}
+
+/// The canonical encoding alphabet, as a SQL string literal
+fn alphabet_literal() -> String {
+    format!("'{}'", std::str::from_utf8(&ENCODE).unwrap())
+}
+
+/// A lookup string with a fixed width slot for each symbol, containing all
+/// the characters that decode to the symbol. Slots are padded by repeating
+/// the canonical encoding, so the first match of any character in the lookup
+/// string is within the slot of its symbol, at `symbol * width`.
+fn decode_lookup() -> (String, usize) {
+    let encodings = encodings();
+    let width = encodings.iter().map(Vec::len).max().unwrap();
+
+    let mut lookup = String::new();
+    for encodings in encodings {
+        for i in 0..width {
+            lookup.push(char::from(*encodings.get(i).unwrap_or(&encodings[0])));
+        }
+    }
+
+    (lookup, width)
+}
+
+impl SqlDialect {
+    /// A `CHECK` constraint enforcing `0 <= id < 2^30` for the given column
+    /// or expression.
+    ///
+    /// ```
+    /// # use id30::SqlDialect;
+    /// assert_eq!(
+    ///     SqlDialect::Sqlite.check_constraint("id"),
+    ///     "CHECK (id >= 0 AND id < 1073741824)"
+    /// );
+    /// ```
+    pub fn check_constraint(self, column: &str) -> String {
+        format!("CHECK ({column} >= 0 AND {column} < {LIMIT})")
+    }
+
+    /// A SQL expression giving the canonical Id30 string for the given
+    /// integer expression, or `NULL` if it is out of range for Id30.
+    ///
+    /// The operand is repeated in the expression, so it should be a column or
+    /// a parameter rather than an expensive expression.
+    pub fn encode_expr(self, operand: &str) -> String {
+        let alphabet = alphabet_literal();
+        let chars: Vec<_> = [25, 20, 15, 10, 5, 0]
+            .iter()
+            .map(|shift| {
+                let symbol = format!("(({operand}) >> {shift}) & 31");
+                match self {
+                    // substr requires an integer position in Postgres
+                    SqlDialect::Postgres => {
+                        format!("substr({alphabet}, ({symbol})::integer + 1, 1)")
+                    }
+                    SqlDialect::MySql | SqlDialect::Sqlite => {
+                        format!("substr({alphabet}, ({symbol}) + 1, 1)")
+                    }
+                }
+            })
+            .collect();
+
+        let concatenated = match self {
+            SqlDialect::Postgres | SqlDialect::Sqlite => chars.join("\n    || "),
+            SqlDialect::MySql => format!("concat(\n        {}\n    )", chars.join(",\n        ")),
+        };
+
+        format!(
+            "CASE WHEN ({operand}) >= 0 AND ({operand}) < {LIMIT} THEN\n    {concatenated}\nEND"
+        )
+    }
+
+    /// A SQL expression giving the integer value of the given Id30 string
+    /// expression, or `NULL` if it is not a valid Id30 string. Non-canonical
+    /// encodings are accepted, like when parsing [`Id30`](crate::Id30).
+    ///
+    /// The operand is repeated in the expression, so it should be a column or
+    /// a parameter rather than an expensive expression.
+    pub fn decode_expr(self, operand: &str) -> String {
+        let (lookup, width) = decode_lookup();
+        let position = |i: usize| {
+            let c = format!("substr({operand}, {i}, 1)");
+            match self {
+                SqlDialect::Postgres => format!("strpos('{lookup}', {c})"),
+                SqlDialect::MySql => format!("instr(_binary'{lookup}', {c})"),
+                SqlDialect::Sqlite => format!("instr('{lookup}', {c})"),
+            }
+        };
+        let div = match self {
+            SqlDialect::Postgres | SqlDialect::Sqlite => "/",
+            SqlDialect::MySql => "DIV",
+        };
+        let length = match self {
+            SqlDialect::Postgres => format!("length({operand}) = 6"),
+            SqlDialect::MySql => format!("char_length({operand}) = 6"),
+            SqlDialect::Sqlite => format!("typeof({operand}) = 'text' AND length({operand}) = 6"),
+        };
+
+        let mut valid = length;
+        let mut value = vec![];
+        for (i, shift) in (1..=6).zip([25, 20, 15, 10, 5, 0]) {
+            let position = position(i);
+            write!(valid, "\n    AND {position} > 0").unwrap();
+            value.push(format!(
+                "(({position} - 1) {div} {width}) * {}",
+                1u32 << shift
+            ));
+        }
+
+        format!(
+            "CASE WHEN {valid}\nTHEN\n    {}\nEND",
+            value.join("\n    + ")
+        )
+    }
+
+    /// SQL for creating functions for working with Id30 values in the
+    /// database:
+    ///
+    ///  - `id30_encode(integer)` gives the canonical Id30 string for an
+    ///    integer
+    ///  - `id30_decode(text)` gives the integer value of an Id30 string, which
+    ///    may be non-canonical
+    ///
+    /// Both functions give `NULL` for `NULL` input. For Postgres, invalid
+    /// input raises an error, and the schema also creates the domain `id30`
+    /// for columns holding Id30 values. MySQL functions can only raise errors
+    /// in compound statements, which require changing the delimiter of the
+    /// `mysql` client, so the MySQL functions give `NULL` for invalid input
+    /// instead. MySQL has no domains, so use [`check_constraint`] on each
+    /// column.
+    ///
+    /// SQLite has no way of defining functions in SQL, so the SQLite schema
+    /// only consists of comments with the `CHECK` constraint and the
+    /// expressions from [`encode_expr`] and [`decode_expr`], which can be used
+    /// in views. To define the functions for SQLite, see the
+    /// `register_sqlite_functions` function of the `rusqlite` integration, or
+    /// the loadable SQLite extension in the `id30` repository.
+    ///
+    /// [`check_constraint`]: SqlDialect::check_constraint
+    /// [`encode_expr`]: SqlDialect::encode_expr
+    /// [`decode_expr`]: SqlDialect::decode_expr
+    pub fn schema(self) -> String {
+        let indent = |sql: String| sql.replace('\n', "\n    ");
+
+        match self {
+            SqlDialect::Postgres => format!(
+                "\
+CREATE DOMAIN id30 AS integer {check};
+
+CREATE OR REPLACE FUNCTION id30_encode(id bigint) RETURNS text
+LANGUAGE plpgsql IMMUTABLE STRICT PARALLEL SAFE AS $$
+DECLARE
+    result text;
+BEGIN
+    result := {encode};
+    IF result IS NULL THEN
+        RAISE EXCEPTION 'integer out of range for Id30: %', id;
+    END IF;
+    RETURN result;
+END
+$$;
+
+CREATE OR REPLACE FUNCTION id30_decode(id30 text) RETURNS integer
+LANGUAGE plpgsql IMMUTABLE STRICT PARALLEL SAFE AS $$
+DECLARE
+    result integer;
+BEGIN
+    result := {decode};
+    IF result IS NULL THEN
+        RAISE EXCEPTION 'invalid Id30: %', id30;
+    END IF;
+    RETURN result;
+END
+$$;
+",
+                check = self.check_constraint("VALUE"),
+                encode = indent(self.encode_expr("id")),
+                decode = indent(self.decode_expr("id30")),
+            ),
+            SqlDialect::MySql => format!(
+                "\
+-- Add the following constraint to columns holding Id30 values:
+-- {check}
+
+DROP FUNCTION IF EXISTS id30_encode;
+CREATE FUNCTION id30_encode(id bigint) RETURNS char(6)
+DETERMINISTIC NO SQL
+RETURN {encode};
+
+DROP FUNCTION IF EXISTS id30_decode;
+CREATE FUNCTION id30_decode(id30 varchar(255)) RETURNS int
+DETERMINISTIC NO SQL
+RETURN {decode};
+",
+                check = self.check_constraint("id"),
+                encode = self.encode_expr("id"),
+                decode = self.decode_expr("id30"),
+            ),
+            SqlDialect::Sqlite => {
+                let comment = |sql: String| sql.replace('\n', "\n-- ");
+                format!(
+                    "\
+-- SQLite cannot define functions in SQL. Add the following constraint to
+-- columns holding Id30 values:
+-- {check}
+
+-- Encode the integer column id to an Id30 string:
+-- {encode}
+
+-- Decode the text column id30 to an integer:
+-- {decode}
+",
+                    check = self.check_constraint("id"),
+                    encode = comment(self.encode_expr("id")),
+                    decode = comment(self.decode_expr("id30")),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::codec_tables::{DECODE, ERR_FLAG};
+
+    #[test]
+    fn parse_dialect() {
+        assert_eq!("postgres".parse(), Ok(SqlDialect::Postgres));
+        assert_eq!("PostgreSQL".parse(), Ok(SqlDialect::Postgres));
+        assert_eq!("mysql".parse(), Ok(SqlDialect::MySql));
+        assert_eq!("sqlite".parse(), Ok(SqlDialect::Sqlite));
+        assert_eq!("oracle".parse::<SqlDialect>(), Err(UnknownDialectError));
+
+        for dialect in [SqlDialect::Postgres, SqlDialect::MySql, SqlDialect::Sqlite] {
+            assert_eq!(dialect.to_string().parse(), Ok(dialect));
+        }
+    }
+
+    #[test]
+    fn decode_lookup_covers_decode_table() {
+        let (lookup, width) = decode_lookup();
+        assert_eq!(lookup.len(), 32 * width);
+
+        for c in 0..=255u8 {
+            let position = lookup.bytes().position(|x| x == c);
+            match DECODE[c as usize] {
+                ERR_FLAG => assert_eq!(position, None, "{c}"),
+                decoded => assert_eq!(
+                    position.map(|p| p / width),
+                    Some((decoded & 0b1_1111) as usize),
+                    "{c}"
+                ),
+            }
+        }
+    }
+
+    #[cfg(feature = "rusqlite030")]
+    mod sqlite {
+        use rusqlite030::{Connection, Result};
+
+        use super::*;
+        use crate::Id30;
+
+        fn query<T: rusqlite030::types::FromSql>(
+            sql: &str,
+            param: impl rusqlite030::ToSql,
+        ) -> Result<T> {
+            let conn = Connection::open_in_memory()?;
+            conn.query_row(sql, [param], |row| row.get(0))
+        }
+
+        fn encode(int: i64) -> Option<String> {
+            let sql = format!("SELECT {}", SqlDialect::Sqlite.encode_expr("?1"));
+            query(&sql, int).unwrap()
+        }
+
+        fn decode(id30: &str) -> Option<i64> {
+            let sql = format!("SELECT {}", SqlDialect::Sqlite.decode_expr("?1"));
+            query(&sql, id30).unwrap()
+        }
+
+        #[test]
+        fn encode_expr() {
+            assert_eq!(encode(0).as_deref(), Some("000000"));
+            assert_eq!(encode(0x1234_5678).as_deref(), Some("938nkr"));
+            assert_eq!(encode(0x3fff_ffff).as_deref(), Some("zzzzzz"));
+            assert_eq!(encode(0x4000_0000), None);
+            assert_eq!(encode(-1), None);
+        }
+
+        #[test]
+        fn decode_expr() {
+            assert_eq!(decode("000000"), Some(0));
+            assert_eq!(decode("bpv3uq"), Some(0x176d_8f57));
+            assert_eq!(decode("BPV3UQ"), Some(0x176d_8f57));
+            assert_eq!(decode("zzzzzz"), Some(0x3fff_ffff));
+            assert_eq!(decode("oOiIlL"), decode("001111"));
+            assert_eq!(decode("sSfF00"), decode("ffff00"));
+            assert_eq!(decode("bpv3u"), None);
+            assert_eq!(decode("bpv3uqq"), None);
+            assert_eq!(decode("bpv3u!"), None);
+            assert_eq!(decode("bpv3ü"), None);
+            assert_eq!(decode("bpv3üq"), None);
+            assert_eq!(decode(""), None);
+        }
+
+        #[test]
+        fn decode_expr_integer() {
+            let sql = format!("SELECT {}", SqlDialect::Sqlite.decode_expr("?1"));
+            assert_eq!(query::<Option<i64>>(&sql, 123456).unwrap(), None);
+        }
+
+        #[test]
+        fn matches_rust_implementation() {
+            let samples = (0..1024u32).map(|i| i.wrapping_mul(0x9e37_79b9) >> 2);
+            for int in samples.chain([0, 0x3fff_ffff]) {
+                let id30 = Id30::try_from(int).unwrap();
+                assert_eq!(encode(int.into()), Some(id30.to_string()));
+                assert_eq!(decode(&id30.to_string()), Some(int.into()));
+                assert_eq!(decode(&id30.to_string().to_uppercase()), Some(int.into()));
+            }
+        }
+
+        #[test]
+        fn check_constraint() -> Result<()> {
+            let conn = Connection::open_in_memory()?;
+            conn.execute(
+                &format!(
+                    "CREATE TABLE items (id INTEGER PRIMARY KEY NOT NULL {})",
+                    SqlDialect::Sqlite.check_constraint("id")
+                ),
+                [],
+            )?;
+
+            for id in [0, 0x3fff_ffff] {
+                conn.execute("INSERT INTO items (id) VALUES (?)", [id])?;
+            }
+            for id in [-1, 0x4000_0000] {
+                assert!(conn
+                    .execute("INSERT INTO items (id) VALUES (?)", [id])
+                    .is_err());
+            }
+
+            Ok(())
+        }
+
+        #[test]
+        fn schema_is_valid_sql() -> Result<()> {
+            let conn = Connection::open_in_memory()?;
+            conn.execute_batch(&SqlDialect::Sqlite.schema())?;
+
+            conn.execute_batch(&format!(
+                "CREATE TABLE items (id INTEGER PRIMARY KEY NOT NULL {});
+                CREATE VIEW item_ids AS SELECT id, {} AS id30 FROM items;
+                INSERT INTO items (id) VALUES (0x12345678);",
+                SqlDialect::Sqlite.check_constraint("id"),
+                SqlDialect::Sqlite.encode_expr("id"),
+            ))?;
+
+            let id30: String = conn.query_row("SELECT id30 FROM item_ids", [], |row| row.get(0))?;
+            assert_eq!(id30, "938nkr");
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "sqlx07")]
+
+use sqlx07::{
+    database::{HasArguments, HasValueRef},
+    encode::IsNull,
+    error::BoxDynError,
+    Database, Decode, Encode, Type,
+};
+
+use crate::Id30;
+
+/// `Id30` has the same SQL type as `i32`, ie `INT4` in Postgres, `INTEGER` in
+/// SQLite and `INT` in MySQL
+impl<DB> Type<DB> for Id30
+where
+    DB: Database,
+    i32: Type<DB>,
+{
+    fn type_info() -> DB::TypeInfo {
+        <i32 as Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <i32 as Type<DB>>::compatible(ty)
+    }
+}
+
+impl<'q, DB> Encode<'q, DB> for Id30
+where
+    DB: Database,
+    i32: Encode<'q, DB>,
+{
+    fn encode_by_ref(&self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        i32::from(*self).encode_by_ref(buf)
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        i32::from(*self).produces()
+    }
+
+    fn size_hint(&self) -> usize {
+        i32::from(*self).size_hint()
+    }
+}
+
+impl<'r, DB> Decode<'r, DB> for Id30
+where
+    DB: Database,
+    i32: Decode<'r, DB>,
+{
+    fn decode(value: <DB as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+        let num = i32::decode(value)?;
+        Ok(Id30::try_from(num)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx07::{Connection, Row, SqliteConnection};
+
+    use super::*;
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn encode() -> Result<(), BoxDynError> {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+        let row = sqlx07::query("SELECT ? AS int")
+            .bind(Id30::try_from(0x1234_5678).unwrap())
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(row.try_get::<i32, _>("int")?, 0x1234_5678);
+
+        Ok(())
+    }
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn decode() -> Result<(), BoxDynError> {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+        let (id30,): (Id30,) = sqlx07::query_as("SELECT 0x12345678 AS id30")
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(id30, Id30::try_from(0x1234_5678).unwrap());
+
+        Ok(())
+    }
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn roundtrip() -> Result<(), BoxDynError> {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+        sqlx07::query("CREATE TABLE items (id INTEGER PRIMARY KEY NOT NULL)")
+            .execute(&mut conn)
+            .await?;
+
+        let id30: Id30 = "bpv3uq".parse().unwrap();
+        sqlx07::query("INSERT INTO items (id) VALUES (?)")
+            .bind(id30)
+            .execute(&mut conn)
+            .await?;
+
+        let (stored,): (Id30,) = sqlx07::query_as("SELECT id FROM items WHERE id = ?")
+            .bind(id30)
+            .fetch_one(&mut conn)
+            .await?;
+        assert_eq!(stored, id30);
+
+        Ok(())
+    }
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn db_invalid_value_gives_error() -> Result<(), BoxDynError> {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await?;
+
+        let res: Result<(Id30,), _> = sqlx07::query_as("SELECT 0x7fffffff AS id30")
+            .fetch_one(&mut conn)
+            .await;
+        assert!(res.is_err());
+
+        let res: Result<(Id30,), _> = sqlx07::query_as("SELECT -1 AS id30")
+            .fetch_one(&mut conn)
+            .await;
+        assert!(res.is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "tower03")]
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use http1::{header::LOCATION, HeaderValue, Request, Response, StatusCode};
+use tower_layer03::Layer;
+use tower_service03::Service;
+
+use crate::{percent_decode::percent_decode, Id30, Id30Parse};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Id30,
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Template {
+    segments: Vec<Segment>,
+}
+
+impl Template {
+    fn parse(template: &str) -> Template {
+        let segments = template
+            .split('/')
+            .map(|segment| match segment {
+                "{id30}" => Segment::Id30,
+                _ if segment.starts_with('{') && segment.ends_with('}') => Segment::Any,
+                _ => Segment::Literal(segment.to_string()),
+            })
+            .collect();
+        Template { segments }
+    }
+
+    /// Match `path` against the template. Yields `None` if it does not match,
+    /// and otherwise the canonical path, if any of the Id30 segments are
+    /// non-canonical.
+    fn canonical_path(&self, path: &str) -> Option<Option<String>> {
+        let segments: Vec<&str> = path.split('/').collect();
+        if segments.len() != self.segments.len() {
+            return None;
+        }
+
+        let mut canonical: Vec<Option<Id30>> = vec![None; segments.len()];
+        for ((template, segment), canonical) in
+            self.segments.iter().zip(&segments).zip(&mut canonical)
+        {
+            match template {
+                Segment::Literal(literal) if *literal != percent_decode(segment)? => return None,
+                Segment::Literal(_) | Segment::Any => {}
+                Segment::Id30 => {
+                    let parse: Id30Parse = percent_decode(segment)?.parse().ok()?;
+                    if !parse.is_canonical {
+                        *canonical = Some(parse.id30);
+                    }
+                }
+            }
+        }
+
+        if canonical.iter().all(Option::is_none) {
+            return Some(None);
+        }
+
+        let canonical: Vec<String> = segments
+            .iter()
+            .zip(canonical)
+            .map(|(segment, id30)| id30.map_or(segment.to_string(), |id30| id30.to_string()))
+            .collect();
+        Some(Some(canonical.join("/")))
+    }
+}
+
+/// A [`Layer`] that redirects requests with non-canonical Id30 path segments
+/// to the canonical URL, for use with any service built on the `http` crate
+/// types, such as axum, tonic-web and hyper services.
+///
+/// The layer is configured with route templates, like `/u/{id30}/posts/{id30}`,
+/// where each path segment is either a literal string that must match
+/// exactly, `{id30}` for an Id30 segment or another placeholder in braces,
+/// such as `{name}`, that matches any segment. Path segments are
+/// percent-decoded before matching. The first template that matches
+/// the request path, including that all the Id30 segments are valid Id30
+/// strings, is used. If any of its Id30 segments are non-canonical, the
+/// request is answered with a single 308 Permanent Redirect to the URL with
+/// all of them canonicalized, preserving the query string. All other requests
+/// are passed through to the inner service untouched.
+///
+/// ```
+/// # use tower_layer03 as tower_layer;
+/// use id30::CanonicalRedirectLayer;
+/// use tower_layer::Layer;
+///
+/// let layer = CanonicalRedirectLayer::new(["/u/{id30}", "/u/{id30}/posts/{id30}"]);
+/// # let service = tower05::service_fn(|_: http1::Request<String>| async {
+/// #     Ok::<_, std::convert::Infallible>(http1::Response::new(String::new()))
+/// # });
+/// let service = layer.layer(service);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CanonicalRedirectLayer {
+    templates: Arc<[Template]>,
+}
+
+impl CanonicalRedirectLayer {
+    /// Create a layer that handles the given route templates
+    pub fn new<I>(templates: I) -> CanonicalRedirectLayer
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        CanonicalRedirectLayer {
+            templates: templates
+                .into_iter()
+                .map(|template| Template::parse(template.as_ref()))
+                .collect(),
+        }
+    }
+}
+
+impl<S> Layer<S> for CanonicalRedirectLayer {
+    type Service = CanonicalRedirect<S>;
+
+    fn layer(&self, inner: S) -> CanonicalRedirect<S> {
+        CanonicalRedirect {
+            inner,
+            templates: self.templates.clone(),
+        }
+    }
+}
+
+/// The [`Service`] created by [`CanonicalRedirectLayer`]
+#[derive(Debug, Clone)]
+pub struct CanonicalRedirect<S> {
+    inner: S,
+    templates: Arc<[Template]>,
+}
+
+impl<S> CanonicalRedirect<S> {
+    fn location(&self, uri: &http1::Uri) -> Option<String> {
+        let mut location = self
+            .templates
+            .iter()
+            .find_map(|template| template.canonical_path(uri.path()))??;
+
+        if let Some(query) = uri.query() {
+            location.push('?');
+            location.push_str(query);
+        }
+        Some(location)
+    }
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for CanonicalRedirect<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    ResBody: Default,
+{
+    type Response = Response<ResBody>;
+    type Error = S::Error;
+    type Future = CanonicalRedirectFuture<S::Future, ResBody>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let kind = match self.location(req.uri()) {
+            Some(location) => {
+                let mut response = Response::new(ResBody::default());
+                *response.status_mut() = StatusCode::PERMANENT_REDIRECT;
+                let location = HeaderValue::try_from(location)
+                    .expect("the location is built from the parts of a valid URI");
+                response.headers_mut().insert(LOCATION, location);
+                Kind::Redirect {
+                    response: Some(response),
+                }
+            }
+            None => Kind::Inner {
+                future: self.inner.call(req),
+            },
+        };
+        CanonicalRedirectFuture { kind }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The response future of [`CanonicalRedirect`]
+    pub struct CanonicalRedirectFuture<F, B> {
+        #[pin]
+        kind: Kind<F, B>,
+    }
+}
+
+pin_project_lite::pin_project! {
+    #[project = KindProj]
+    enum Kind<F, B> {
+        Inner {
+            #[pin]
+            future: F,
+        },
+        Redirect {
+            response: Option<Response<B>>,
+        },
+    }
+}
+
+impl<F, B, E> Future for CanonicalRedirectFuture<F, B>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+{
+    type Output = Result<Response<B>, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().kind.project() {
+            KindProj::Inner { future } => future.poll(cx),
+            KindProj::Redirect { response } => {
+                Poll::Ready(Ok(response.take().expect("polled after completion")))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::Infallible;
+
+    use tower05::{service_fn, ServiceExt};
+
+    use super::*;
+
+    async fn call(uri: &str) -> (StatusCode, String) {
+        let layer = CanonicalRedirectLayer::new([
+            "/u/{id30}",
+            "/u/{id30}/posts/{id30}",
+            "/teams/{name}/{id30}",
+        ]);
+        let service = layer.layer(service_fn(|req: Request<String>| async move {
+            Ok::<_, Infallible>(Response::new(format!("inner {}", req.uri())))
+        }));
+
+        let response = service
+            .oneshot(Request::get(uri).body(String::new()).unwrap())
+            .await
+            .unwrap();
+        match response.headers().get(LOCATION) {
+            Some(location) => (response.status(), location.to_str().unwrap().to_string()),
+            None => (response.status(), response.into_body()),
+        }
+    }
+
+    fn redirect(location: &str) -> (StatusCode, String) {
+        (StatusCode::PERMANENT_REDIRECT, location.to_string())
+    }
+
+    fn inner(uri: &str) -> (StatusCode, String) {
+        (StatusCode::OK, format!("inner {uri}"))
+    }
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn passes_through() {
+        for uri in [
+            "/u/bpv3uq",
+            "/u/bpv3uq/posts/zvaec2?x=1",
+            "/u/BPV3UQ/edit",
+            "/u/invalid",
+            "/u/BPV3UQ/posts/invalid",
+            "/other/BPV3UQ",
+            "/teams/Rust/bpv3uq",
+            "/u/%62pv3uq",
+            "/u/%zzpv3uq",
+        ] {
+            assert_eq!(call(uri).await, inner(uri), "{uri}");
+        }
+    }
+
+    #[tokio1::test(crate = "tokio1")]
+    async fn redirects() {
+        assert_eq!(call("/u/BPV3UQ").await, redirect("/u/bpv3uq"));
+        assert_eq!(
+            call("/u/BPV3UQ/posts/zvaec2?x=1").await,
+            redirect("/u/bpv3uq/posts/zvaec2?x=1")
+        );
+        assert_eq!(
+            call("/u/BPV3UQ/posts/ZVAEC2").await,
+            redirect("/u/bpv3uq/posts/zvaec2")
+        );
+        assert_eq!(
+            call("/teams/Rust/b0v3oq").await,
+            redirect("/teams/Rust/b0v30q")
+        );
+        assert_eq!(call("/%75/%42PV3UQ").await, redirect("/%75/bpv3uq"));
+    }
+
+    #[test]
+    fn templates() {
+        assert_eq!(
+            Template::parse("/u/{id30}/{name}").segments,
+            [
+                Segment::Literal(String::new()),
+                Segment::Literal("u".to_string()),
+                Segment::Id30,
+                Segment::Any,
+            ]
+        );
+    }
+}
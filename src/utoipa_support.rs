@@ -0,0 +1,192 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "utoipa5")]
+
+use std::borrow::Cow;
+
+use utoipa5::{
+    openapi::{
+        schema::{ObjectBuilder, Ref, Schema, Type},
+        RefOr,
+    },
+    PartialSchema, ToSchema,
+};
+
+use crate::{
+    schema::{CANONICAL_PATTERN, PATTERN},
+    CanonicalId30, Id30, Id30Parse,
+};
+
+fn string_schema(pattern: &str) -> RefOr<Schema> {
+    ObjectBuilder::new()
+        .schema_type(Type::String)
+        .min_length(Some(6))
+        .max_length(Some(6))
+        .pattern(Some(pattern))
+        .examples(["bpv3uq"])
+        .into()
+}
+
+/// `Id30` is described as a string in any valid encoding, matching how it is
+/// deserialized from human readable formats and path parameters
+impl PartialSchema for Id30 {
+    fn schema() -> RefOr<Schema> {
+        string_schema(PATTERN)
+    }
+}
+
+impl ToSchema for Id30 {
+    fn name() -> Cow<'static, str> {
+        "Id30".into()
+    }
+}
+
+/// `CanonicalId30` is described as a string in the canonical encoding
+impl PartialSchema for CanonicalId30 {
+    fn schema() -> RefOr<Schema> {
+        string_schema(CANONICAL_PATTERN)
+    }
+}
+
+impl ToSchema for CanonicalId30 {
+    fn name() -> Cow<'static, str> {
+        "CanonicalId30".into()
+    }
+}
+
+/// `Id30Parse` is described as an object with the fields `id30` and
+/// `is_canonical`, matching how it is serialized. Human readable formats also
+/// accept this form when deserializing, in addition to plain Id30 strings.
+impl PartialSchema for Id30Parse {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .property("id30", Ref::from_schema_name(Id30::name()))
+            .required("id30")
+            .property("is_canonical", bool::schema())
+            .required("is_canonical")
+            .into()
+    }
+}
+
+impl ToSchema for Id30Parse {
+    fn name() -> Cow<'static, str> {
+        "Id30Parse".into()
+    }
+
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        schemas.push((Id30::name().into(), Id30::schema()));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json1::json;
+    use utoipa5 as utoipa;
+    use utoipa5::OpenApi;
+
+    use super::*;
+
+    #[test]
+    fn id30() {
+        assert_eq!(
+            serde_json1::to_value(Id30::schema()).unwrap(),
+            json!({
+                "type": "string",
+                "minLength": 6,
+                "maxLength": 6,
+                "pattern": "^[0-9A-Za-z]{6}$",
+                "examples": ["bpv3uq"],
+            })
+        );
+    }
+
+    #[test]
+    fn id30parse() {
+        assert_eq!(
+            serde_json1::to_value(Id30Parse::schema()).unwrap(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "id30": { "$ref": "#/components/schemas/Id30" },
+                    "is_canonical": { "type": "boolean" },
+                },
+                "required": ["id30", "is_canonical"],
+            })
+        );
+
+        let mut schemas = Vec::new();
+        Id30Parse::schemas(&mut schemas);
+        assert_eq!(schemas.len(), 1);
+        assert_eq!(schemas[0].0, "Id30");
+    }
+
+    #[cfg(feature = "serde1")]
+    #[test]
+    fn id30parse_matches_serialize() {
+        let parse = Id30Parse {
+            id30: "bpv3uq".parse().unwrap(),
+            is_canonical: true,
+        };
+        assert_eq!(
+            serde_json1::to_value(parse).unwrap(),
+            json!({ "id30": "bpv3uq", "is_canonical": true })
+        );
+    }
+
+    #[test]
+    fn canonical_id30() {
+        assert_eq!(
+            serde_json1::to_value(CanonicalId30::schema()).unwrap(),
+            json!({
+                "type": "string",
+                "minLength": 6,
+                "maxLength": 6,
+                "pattern": "^[0-9a-hjkmnp-rt-z]{6}$",
+                "examples": ["bpv3uq"],
+            })
+        );
+    }
+
+    #[allow(unused)]
+    #[derive(utoipa::ToSchema)]
+    struct Post {
+        id: Id30,
+        #[schema(value_type = CanonicalId30)]
+        parent: Id30,
+    }
+
+    #[utoipa::path(get, path = "/posts/{id}", params(("id" = Id30, Path)))]
+    #[allow(unused)]
+    fn get_post() {}
+
+    #[derive(OpenApi)]
+    #[openapi(paths(get_post), components(schemas(Post)))]
+    struct ApiDoc;
+
+    #[test]
+    fn openapi() {
+        let openapi = serde_json1::to_value(ApiDoc::openapi()).unwrap();
+
+        assert_eq!(
+            openapi.pointer("/components/schemas/Post/properties"),
+            Some(&json!({
+                "id": { "$ref": "#/components/schemas/Id30" },
+                "parent": { "$ref": "#/components/schemas/CanonicalId30" },
+            }))
+        );
+        assert_eq!(
+            openapi.pointer("/components/schemas/Id30"),
+            Some(&serde_json1::to_value(Id30::schema()).unwrap())
+        );
+        assert_eq!(
+            openapi.pointer("/paths/~1posts~1{id}/get/parameters/0/schema"),
+            Some(&json!({ "$ref": "#/components/schemas/Id30" }))
+        );
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The word list for [`Id30::to_words`][crate::Id30::to_words].
+//!
+//! The list is a subset of the BIP-39 English word list, selected to consist
+//! of words of four to six letters, with no two words within one edit of each
+//! other and no two words that sound alike. It is sorted, so the word encoding
+//! of a value is ordered like the value itself.
+
+pub(crate) const WORDS: [&str; 1024] = [
+    "able", "about", "above", "absent", "absorb", "accuse", "acid", "action", "actor", "actual",
+    "adapt", "adjust", "admit", "adult", "advice", "affair", "afford", "afraid", "agent", "agree",
+    "aisle", "alarm", "album", "alert", "alien", "alley", "allow", "almost", "alpha", "alter",
+    "always", "among", "amount", "amused", "anchor", "angle", "animal", "annual", "answer",
+    "apart", "appear", "apple", "arch", "arctic", "area", "argue", "armed", "armor", "army",
+    "around", "arrive", "arrow", "artist", "aspect", "asset", "assist", "assume", "atom", "attack",
+    "attend", "audit", "aunt", "author", "auto", "autumn", "awake", "away", "axis", "baby",
+    "bacon", "badge", "ball", "bamboo", "banana", "banner", "barely", "barrel", "base", "basic",
+    "basket", "battle", "beach", "bean", "beauty", "become", "beef", "before", "begin", "behave",
+    "behind", "below", "belt", "better", "bike", "bind", "birth", "black", "blade", "blast",
+    "bless", "blue", "blush", "board", "boat", "body", "bonus", "boost", "boring", "borrow",
+    "bottom", "bounce", "brain", "brand", "brass", "brave", "bread", "brick", "bridge", "brief",
+    "bright", "broken", "bronze", "broom", "brown", "bubble", "budget", "build", "bulb", "bundle",
+    "bunker", "burden", "burger", "burst", "busy", "buyer", "cabin", "cactus", "cage", "calm",
+    "camera", "camp", "canal", "cancel", "candy", "cannon", "canoe", "canvas", "carbon", "card",
+    "cargo", "carpet", "carry", "cash", "castle", "casual", "catch", "caught", "celery", "cement",
+    "census", "cereal", "chalk", "change", "chaos", "chat", "cheap", "check", "chef", "cherry",
+    "chest", "child", "chunk", "churn", "circle", "city", "civil", "claim", "clap", "clean",
+    "clerk", "clever", "click", "client", "cliff", "clinic", "clog", "close", "cloth", "cloud",
+    "clown", "club", "clump", "clutch", "coach", "code", "coffee", "coil", "color", "column",
+    "comic", "common", "cook", "copper", "copy", "coral", "corn", "cost", "cotton", "couple",
+    "course", "cousin", "cradle", "craft", "cram", "crane", "crater", "crawl", "crazy", "credit",
+    "creek", "crew", "crisp", "critic", "crop", "cross", "crouch", "crowd", "crunch", "crush",
+    "cube", "curve", "custom", "cycle", "dance", "daring", "dawn", "deal", "debate", "debris",
+    "decade", "deer", "define", "defy", "degree", "delay", "demand", "denial", "depart", "depend",
+    "depth", "deputy", "desert", "design", "desk", "detail", "detect", "device", "devote", "diary",
+    "dice", "diesel", "diet", "differ", "direct", "dish", "divert", "divide", "dizzy", "doctor",
+    "domain", "donate", "donkey", "donor", "double", "dove", "dragon", "drama", "draw", "dress",
+    "drift", "drill", "drink", "drip", "drive", "drum", "duck", "dune", "dust", "duty", "dwarf",
+    "eager", "eagle", "early", "earn", "earth", "easily", "east", "echo", "edge", "edit", "effort",
+    "eight", "elbow", "elder", "elite", "embark", "embody", "employ", "empty", "enable", "enact",
+    "enemy", "energy", "engage", "engine", "enjoy", "enlist", "enough", "enrich", "enroll",
+    "ensure", "enter", "entry", "equal", "equip", "erase", "erode", "error", "erupt", "escape",
+    "essay", "estate", "ethics", "evoke", "evolve", "excess", "excite", "excuse", "exist",
+    "exotic", "expand", "expect", "expire", "expose", "extend", "extra", "fabric", "face", "faint",
+    "faith", "false", "family", "famous", "fancy", "farm", "father", "fault", "feed", "fence",
+    "fetch", "fever", "fiber", "figure", "file", "filter", "final", "finger", "finish", "first",
+    "fiscal", "flag", "flame", "flash", "flavor", "flee", "flight", "flip", "float", "flock",
+    "floor", "flower", "fluid", "foam", "focus", "fold", "follow", "foot", "force", "forest",
+    "forget", "fork", "forum", "fossil", "foster", "found", "fresh", "friend", "fringe", "frog",
+    "front", "frozen", "fruit", "funny", "future", "gadget", "gain", "galaxy", "game", "garage",
+    "garden", "garlic", "gasp", "gauge", "genius", "genre", "gentle", "ghost", "giant", "gift",
+    "giggle", "girl", "give", "glad", "glance", "glare", "glass", "globe", "glory", "glow", "good",
+    "goose", "gospel", "govern", "gown", "grab", "grace", "great", "green", "grid", "group",
+    "grunt", "guard", "guitar", "habit", "hair", "half", "hand", "happy", "harbor", "harsh",
+    "have", "hawk", "hazard", "head", "health", "heavy", "helmet", "help", "hero", "hidden",
+    "high", "hill", "hint", "hobby", "hockey", "home", "honey", "horse", "hotel", "human",
+    "humble", "humor", "hungry", "hurdle", "hybrid", "icon", "idea", "idle", "ignore", "image",
+    "immune", "impact", "impose", "inch", "income", "index", "indoor", "infant", "inform",
+    "inhale", "inject", "inner", "input", "inside", "intact", "invest", "invite", "island",
+    "issue", "item", "ivory", "jacket", "jaguar", "jazz", "jeans", "jelly", "jewel", "join",
+    "joke", "judge", "jump", "jungle", "junior", "junk", "keen", "kidney", "kiss", "kite", "kiwi",
+    "knee", "knife", "know", "label", "labor", "ladder", "lady", "lake", "laptop", "large",
+    "laugh", "lava", "leaf", "leave", "left", "legal", "legend", "lemon", "lend", "length",
+    "lesson", "level", "limb", "limit", "link", "lion", "liquid", "list", "little", "lizard",
+    "load", "local", "logic", "lonely", "long", "loop", "lucky", "lumber", "lunar", "lunch",
+    "luxury", "lyrics", "magic", "magnet", "maid", "major", "mammal", "manage", "mango", "manual",
+    "maple", "marble", "margin", "marine", "market", "mask", "master", "math", "matrix", "maze",
+    "meadow", "meat", "medal", "media", "melody", "member", "memory", "menu", "mercy", "merge",
+    "merit", "mesh", "method", "middle", "milk", "mimic", "minor", "minute", "mirror", "mixed",
+    "mobile", "modify", "moment", "month", "moon", "motion", "motor", "movie", "muffin", "mule",
+    "muscle", "museum", "music", "mutual", "naive", "napkin", "nation", "nature", "near", "neck",
+    "nephew", "nerve", "nest", "news", "noble", "noodle", "normal", "north", "nose", "notice",
+    "novel", "nurse", "obey", "object", "oblige", "obtain", "occur", "ocean", "odor", "offer",
+    "office", "often", "olive", "omit", "onion", "online", "open", "opera", "oppose", "option",
+    "orange", "orbit", "order", "organ", "orient", "outer", "output", "oval", "over", "owner",
+    "oxygen", "oyster", "pact", "paddle", "panda", "panel", "panic", "paper", "parade", "parent",
+    "park", "parrot", "party", "pass", "patrol", "peanut", "pencil", "people", "permit", "person",
+    "phone", "photo", "phrase", "piano", "picnic", "pigeon", "pilot", "pipe", "pitch", "pizza",
+    "place", "planet", "play", "pledge", "pluck", "plug", "plunge", "poem", "point", "polar",
+    "pole", "police", "pond", "potato", "power", "prefer", "pretty", "price", "profit", "proud",
+    "public", "pulp", "pulse", "pupil", "puppy", "purity", "push", "puzzle", "quick", "quit",
+    "rack", "radar", "radio", "rail", "raise", "ranch", "random", "rapid", "rare", "raven",
+    "razor", "reason", "rebel", "recall", "recipe", "record", "reduce", "reform", "region",
+    "relax", "relief", "rely", "remain", "remind", "remove", "render", "renew", "rent", "reopen",
+    "repair", "repeat", "report", "rescue", "resist", "result", "retire", "return", "review",
+    "reward", "rhythm", "ribbon", "rich", "ride", "rifle", "rigid", "ring", "ripple", "risk",
+    "ritual", "rival", "river", "roast", "robot", "robust", "rocket", "roof", "rookie", "rotate",
+    "rough", "rubber", "runway", "rural", "safe", "salad", "salon", "salt", "salute", "sample",
+    "scale", "scan", "scene", "scheme", "school", "scout", "scrap", "screen", "script", "scrub",
+    "search", "second", "secret", "seek", "select", "sell", "senior", "sense", "series", "settle",
+    "setup", "seven", "shadow", "shaft", "share", "shed", "shield", "shine", "ship", "shiver",
+    "shock", "shoe", "shoot", "shrimp", "shrug", "siege", "sign", "silent", "silver", "siren",
+    "sister", "size", "sketch", "skirt", "slab", "sleep", "slice", "slim", "slogan", "slot",
+    "small", "smart", "smoke", "smooth", "snack", "snap", "sniff", "soccer", "social", "soda",
+    "soft", "solve", "sorry", "soup", "source", "south", "space", "spawn", "speak", "speed",
+    "sphere", "spider", "spin", "spirit", "split", "spoil", "sport", "spray", "spread", "spring",
+    "square", "stable", "staff", "stage", "stairs", "stamp", "stand", "stay", "steel", "step",
+    "stick", "sting", "story", "stove", "street", "strike", "strong", "submit", "subway", "sudden",
+    "summer", "sunset", "super", "supply", "sure", "survey", "swarm", "swear", "sweet", "swift",
+    "switch", "symbol", "syrup", "system", "tackle", "talent", "talk", "tape", "target", "taste",
+    "tattoo", "taxi", "team", "tenant", "tennis", "text", "thank", "theme", "theory", "thing",
+    "thrive", "throw", "thumb", "ticket", "tiger", "tilt", "timber", "tiny", "tired", "title",
+    "today", "token", "tomato", "tone", "tongue", "tool", "tooth", "topic", "topple", "torch",
+    "toss", "total", "toward", "trade", "trap", "travel", "tree", "trend", "trial", "tribe",
+    "trim", "trophy", "truck", "truly", "trust", "truth", "tunnel", "turkey", "turn", "turtle",
+    "twelve", "twenty", "twice", "twin", "twist", "uncle", "under", "undo", "unfair", "unfold",
+    "unique", "unit", "unlock", "until", "unveil", "update", "uphold", "upper", "upset", "urban",
+    "urge", "usage", "useful", "vacant", "vacuum", "vague", "valid", "valve", "vanish", "vapor",
+    "velvet", "vendor", "venue", "verb", "verify", "vessel", "viable", "video", "view", "violin",
+    "visa", "visit", "vital", "vivid", "voice", "void", "volume", "vote", "voyage", "wagon",
+    "wait", "walnut", "water", "weasel", "whale", "width", "wife", "wild", "window", "winter",
+    "wisdom", "wolf", "woman", "wonder", "word", "young", "zebra",
+];
@@ -0,0 +1,229 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::{word_list::WORDS, Id30, Id30Parse, ParseError};
+
+/// The edit distance between `a` and `b`, counting insertions, deletions,
+/// substitutions and transpositions of adjacent characters
+fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+    let mut prev2: Vec<usize> = vec![0; b.len() + 1];
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        cur[0] = i;
+        for j in 1..=b.len() {
+            let substitution = prev[j - 1] + (a[i - 1] != b[j - 1]) as usize;
+            cur[j] = substitution.min(prev[j] + 1).min(cur[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                cur[j] = cur[j].min(prev2[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut prev2, &mut prev);
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
+/// Look up `word` in the word list, yielding its index
+fn lookup(word: &str) -> Option<u32> {
+    let lower = word.to_ascii_lowercase();
+    if let Ok(index) = WORDS.binary_search(&lower.as_str()) {
+        return Some(index as u32);
+    }
+
+    // Misspellings are resolved to the closest word, as long as it is close
+    // enough and unambiguous. No two words in the list are within one edit of
+    // each other.
+    let max_distance = if lower.len() >= 5 { 2 } else { 1 };
+    let mut best = None;
+    let mut ambiguous = false;
+    for (index, candidate) in WORDS.iter().enumerate() {
+        let distance = edit_distance(lower.as_bytes(), candidate.as_bytes());
+        match best {
+            _ if distance > max_distance => {}
+            Some((_, best_distance)) if distance > best_distance => {}
+            Some((_, best_distance)) if distance == best_distance => ambiguous = true,
+            _ => {
+                best = Some((index, distance));
+                ambiguous = false;
+            }
+        }
+    }
+
+    match best {
+        Some((index, _)) if !ambiguous => Some(index as u32),
+        _ => None,
+    }
+}
+
+impl Id30 {
+    /// Render the Id30 as three words, separated by `-`.
+    ///
+    /// Each word encodes ten bits of the value, by indexing into a built-in
+    /// list of 1024 short and distinct English words. The list is sorted, so
+    /// the lexicographical ordering of the word encodings matches the ordering
+    /// of the values.
+    ///
+    /// ```
+    /// # use id30::Id30;
+    /// let id: Id30 = "bpv3uq".parse().unwrap();
+    /// assert_eq!(id.to_words(), "flee-square-speak");
+    /// ```
+    pub fn to_words(&self) -> String {
+        let words: Vec<&str> = [20, 10, 0]
+            .iter()
+            .map(|shift| WORDS[((self.0 >> shift) & 0b11_1111_1111) as usize])
+            .collect();
+        words.join("-")
+    }
+
+    /// Parse the three word encoding of an Id30, as generated by
+    /// [`Id30::to_words`].
+    ///
+    /// See [`Id30Parse::from_words`] for details about accepted input.
+    pub fn from_words(s: &str) -> Result<Id30, ParseError> {
+        Id30Parse::from_words(s).map(|x| x.id30)
+    }
+}
+
+impl Id30Parse {
+    /// Parse the three word encoding of an Id30, as generated by
+    /// [`Id30::to_words`].
+    ///
+    /// Words may be separated by `-`, whitespace, `_`, `.` or `,`, and are
+    /// matched case-insensitively. Misspelled words are corrected to the
+    /// closest word in the list, as long as there is a single closest word
+    /// within one edit, or two edits for words of five letters or more.
+    ///
+    /// The input is canonical when it is exactly as generated by
+    /// [`Id30::to_words`], that is, with lower case words separated by a
+    /// single `-`. Parsing yields [`ParseError::InvalidLength`] if the input
+    /// does not consist of three words, and [`ParseError::InvalidCharacters`]
+    /// if a word is not recognized.
+    ///
+    /// ```
+    /// # fn main() -> Result<(), id30::ParseError> {
+    /// use id30::Id30Parse;
+    ///
+    /// let parse = Id30Parse::from_words("flee-square-speak")?;
+    /// assert_eq!(&parse.id30.to_string(), "bpv3uq");
+    /// assert!(parse.is_canonical);
+    ///
+    /// let parse = Id30Parse::from_words("Flee sqaure spaek")?;
+    /// assert_eq!(&parse.id30.to_string(), "bpv3uq");
+    /// assert!(!parse.is_canonical);
+    /// # Ok(())}
+    /// ```
+    pub fn from_words(s: &str) -> Result<Id30Parse, ParseError> {
+        let mut words = s
+            .split(|c: char| c.is_whitespace() || matches!(c, '-' | '_' | '.' | ','))
+            .filter(|word| !word.is_empty());
+
+        let mut value = 0;
+        for _ in 0..3 {
+            let word = words.next().ok_or(ParseError::InvalidLength)?;
+            let index = lookup(word).ok_or(ParseError::InvalidCharacters)?;
+            value = (value << 10) | index;
+        }
+
+        if words.next().is_some() {
+            return Err(ParseError::InvalidLength);
+        }
+
+        let id30 = Id30(value);
+        let is_canonical = s == id30.to_words();
+
+        Ok(Id30Parse { id30, is_canonical })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn word_list_properties() {
+        assert!(WORDS.windows(2).all(|pair| pair[0] < pair[1]));
+
+        for (i, a) in WORDS.iter().enumerate() {
+            assert!((4..=6).contains(&a.len()), "{a}");
+            assert!(a.bytes().all(|c| c.is_ascii_lowercase()), "{a}");
+            for b in &WORDS[i + 1..] {
+                assert!(edit_distance(a.as_bytes(), b.as_bytes()) >= 2, "{a} {b}");
+            }
+        }
+    }
+
+    #[test]
+    fn to_words() {
+        assert_eq!(Id30(0).to_words(), "able-able-able");
+        assert_eq!(Id30((1 << 30) - 1).to_words(), "zebra-zebra-zebra");
+        assert_eq!(Id30(1025).to_words(), "able-about-about");
+    }
+
+    #[test]
+    fn roundtrip_and_ordering() {
+        let mut prev = String::new();
+        for i in (0..1 << 30).step_by(999_983) {
+            let id30 = Id30(i);
+            let words = id30.to_words();
+            assert!(prev < words);
+            assert_eq!(
+                Id30Parse::from_words(&words),
+                Ok(Id30Parse {
+                    id30,
+                    is_canonical: true
+                })
+            );
+            prev = words;
+        }
+    }
+
+    #[test]
+    fn non_canonical() {
+        let expected = Id30::from_words("flee-square-speak").unwrap();
+
+        for input in [
+            "FLEE-SQUARE-SPEAK",
+            "flee square speak",
+            "flee--square-speak",
+            " flee-square-speak",
+            "flee_square.speak",
+            "flea-square-speak",
+            "flee-sqaure-speak",
+            "flee-square-speakt",
+        ] {
+            assert_eq!(
+                Id30Parse::from_words(input),
+                Ok(Id30Parse {
+                    id30: expected,
+                    is_canonical: false
+                }),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Id30Parse::from_words("flee-square"),
+            Err(ParseError::InvalidLength)
+        );
+        assert_eq!(
+            Id30Parse::from_words("flee-square-speak-able"),
+            Err(ParseError::InvalidLength)
+        );
+        assert_eq!(
+            Id30Parse::from_words("flee-square-qqqqqq"),
+            Err(ParseError::InvalidCharacters)
+        );
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright 2024 Magnus Hovland Hoff.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or https://opensource.org/license/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Tests for the SQLite loadable extension in `sqlite-extension/`, which is a
+//! separate package that must be built with cargo before it can be loaded
+//! into the bundled SQLite used here.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::OnceLock,
+};
+
+use rusqlite030::{types::FromSql, Connection, LoadExtensionGuard, Result};
+
+fn extension_path() -> &'static Path {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("sqlite-extension");
+
+        let status = Command::new(env!("CARGO"))
+            .arg("build")
+            .arg("--manifest-path")
+            .arg(manifest_dir.join("sqlite-extension/Cargo.toml"))
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .status()
+            .expect("failed to run cargo");
+        assert!(status.success(), "failed to build the SQLite extension");
+
+        let file_name = format!(
+            "{}id30{}",
+            std::env::consts::DLL_PREFIX,
+            std::env::consts::DLL_SUFFIX
+        );
+        target_dir.join("debug").join(file_name)
+    })
+}
+
+fn conn() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    unsafe {
+        let _guard = LoadExtensionGuard::new(&conn).unwrap();
+        conn.load_extension(extension_path(), None).unwrap();
+    }
+    conn
+}
+
+fn query<T: FromSql>(conn: &Connection, sql: &str) -> Result<T> {
+    conn.query_row(sql, [], |row| row.get(0))
+}
+
+#[test]
+fn functions() -> Result<()> {
+    let conn = conn();
+
+    assert_eq!(
+        query::<String>(&conn, "SELECT id30_encode(0x12345678)")?,
+        "938nkr"
+    );
+    assert_eq!(
+        query::<i64>(&conn, "SELECT id30_decode('938nkr')")?,
+        0x1234_5678
+    );
+    assert_eq!(
+        query::<i64>(&conn, "SELECT id30_decode('938NKR')")?,
+        0x1234_5678
+    );
+    assert_eq!(
+        query::<String>(&conn, "SELECT id30_canonicalize('938NKR')")?,
+        "938nkr"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn random() -> Result<()> {
+    let conn = conn();
+
+    let ids: Vec<i64> = conn
+        .prepare("SELECT id30_random() FROM (SELECT 1 UNION ALL SELECT 2 UNION ALL SELECT 3)")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_>>()?;
+    assert_eq!(ids.len(), 3);
+    assert!(ids.iter().all(|&id| (0..1 << 30).contains(&id)));
+
+    // Random values are not considered constant within a statement
+    assert!(ids[0] != ids[1] || ids[1] != ids[2]);
+
+    Ok(())
+}
+
+#[test]
+fn null_input() -> Result<()> {
+    let conn = conn();
+
+    for function in [
+        "id30_encode",
+        "id30_try_encode",
+        "id30_decode",
+        "id30_try_decode",
+        "id30_canonicalize",
+        "id30_try_canonicalize",
+    ] {
+        let res: Option<String> = query(&conn, &format!("SELECT {function}(NULL)"))?;
+        assert_eq!(res, None, "{function}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn invalid_input() -> Result<()> {
+    let conn = conn();
+
+    for (function, arg) in [
+        ("encode", "0x40000000"),
+        ("encode", "-1"),
+        ("encode", "'938nkr'"),
+        ("decode", "'938nk'"),
+        ("decode", "'938nk!'"),
+        ("decode", "1"),
+        ("canonicalize", "'938nk'"),
+    ] {
+        let sql = format!("SELECT id30_{function}({arg})");
+        assert!(query::<Option<String>>(&conn, &sql).is_err(), "{sql}");
+
+        let sql = format!("SELECT id30_try_{function}({arg})");
+        assert_eq!(query::<Option<String>>(&conn, &sql)?, None, "{sql}");
+    }
+
+    let err = query::<String>(&conn, "SELECT id30_decode('938nk')").unwrap_err();
+    assert!(err.to_string().contains("invalid length"), "{err}");
+
+    Ok(())
+}
+
+#[test]
+fn deterministic() -> Result<()> {
+    let conn = conn();
+
+    conn.execute_batch(
+        "CREATE TABLE posts (id INTEGER PRIMARY KEY NOT NULL);
+        CREATE INDEX posts_id30 ON posts (id30_encode(id));",
+    )?;
+    assert!(conn
+        .execute_batch("CREATE INDEX posts_random ON posts (id30_random());")
+        .is_err());
+
+    Ok(())
+}